@@ -36,41 +36,452 @@ for item in queue.iter() {
 
 */
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+#[cfg(feature = "runtime")]
+use std::task::Waker;
+#[cfg(feature = "runtime")]
+use std::time::{Duration, Instant};
 use tick_id::TickId;
 
+mod multi;
+#[cfg(feature = "futures")]
+mod stream;
+
+pub use multi::MultiQueue;
+
+#[cfg(feature = "futures")]
+pub use stream::{QueueHandle, QueueStream};
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ItemInfo<T> {
     pub item: T,
     pub tick_id: TickId,
 }
 
+impl<T> ItemInfo<T> {
+    #[must_use]
+    pub const fn new(tick_id: TickId, item: T) -> Self {
+        Self { item, tick_id }
+    }
+}
+
 impl<T: Display> Display for ItemInfo<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}: {}", self.tick_id, self.item)
     }
 }
 
-#[derive(Debug)]
+impl<T: Hash> Hash for ItemInfo<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.item.hash(state);
+        self.tick_id.value().hash(state);
+    }
+}
+
+/// Cumulative counters of what went through a queue, see [`Queue::metrics`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueueMetrics {
+    /// Items pushed, by any of the push methods.
+    pub pushed: u64,
+    /// Items removed and handed to the caller by [`Queue::pop`], [`Queue::take`] and
+    /// [`Queue::split_off_front`]. Clones handed out past a moved read position are not counted.
+    pub popped: u64,
+    /// Items dropped without being handed to the caller, from either end.
+    pub discarded: u64,
+    /// The largest number of items held at once, not to be confused with the configured
+    /// [`Queue::max_len`] limit.
+    pub peak_len: usize,
+}
+
+impl QueueMetrics {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pushed: 0,
+            popped: 0,
+            discarded: 0,
+            peak_len: 0,
+        }
+    }
+}
+
+#[cfg(feature = "runtime")]
+type DiscardCallback<T> = Box<dyn FnMut(ItemInfo<T>) + Send>;
+
 pub struct Queue<T> {
     items: VecDeque<ItemInfo<T>>,
     expected_write_id: TickId, // Tracks the next TickId to be written, ensuring continuity even when the queue is empty
+    tick_step: u32,            // How many ticks each stored item advances the sequence
+    expected_read_id: TickId, // Tracks the next TickId to be read, advanced by pops, takes and discards
+    #[cfg(feature = "runtime")]
+    waker: Option<Waker>, // Woken when the queue goes from empty to non-empty
+    #[cfg(feature = "runtime")]
+    on_discard: Option<DiscardCallback<T>>, // Receives items that are discarded rather than handed to the caller
+    #[cfg(feature = "runtime")]
+    arrivals: VecDeque<Option<Instant>>, // Arrival instant per item, parallel to `items` once `push_at` was used and empty before
+    metrics: QueueMetrics,           // Cumulative counters, kept across `clear`
+    max_len: Option<usize>,          // Pushes beyond this many items are rejected, if set
+    auto_trim_target: Option<usize>, // Pushes discard the oldest items to stay at this length, if set
+    #[cfg(debug_assertions)]
+    monotonic_check: Option<Option<TickId>>, // When enabled, the last popped tick, if any
+}
+
+impl<T: Debug> Debug for Queue<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Queue");
+        debug
+            .field("items", &self.items)
+            .field("expected_write_id", &self.expected_write_id)
+            .field("tick_step", &self.tick_step)
+            .field("expected_read_id", &self.expected_read_id)
+            .field("metrics", &self.metrics)
+            .field("max_len", &self.max_len)
+            .field("auto_trim_target", &self.auto_trim_target);
+        #[cfg(debug_assertions)]
+        debug.field("monotonic_check", &self.monotonic_check);
+        #[cfg(feature = "runtime")]
+        debug
+            .field("waker", &self.waker)
+            .field("has_on_discard", &self.on_discard.is_some());
+        debug.finish()
+    }
 }
 
+/// Creates an empty queue starting at tick zero. Prefer [`Queue::empty_at`] or [`Queue::new`]
+/// when the starting tick matters, so it is visible at the call site.
 impl<T> Default for Queue<T> {
     fn default() -> Self {
-        Self {
-            items: Default::default(),
-            expected_write_id: Default::default(),
+        Self::new(TickId::default())
+    }
+}
+
+/// Two queues are equal if they hold the same items, expect the same next write tick and
+/// advance by the same tick step.
+impl<T: PartialEq> PartialEq for Queue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+            && self.expected_write_id == other.expected_write_id
+            && self.tick_step == other.tick_step
+    }
+}
+
+impl<T: Eq> Eq for Queue<T> {}
+
+impl<T: Hash> Hash for Queue<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+        self.expected_write_id.value().hash(state);
+        self.tick_step.hash(state);
+    }
+}
+
+impl<T: Display> Display for Queue<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Queue(expected_write={})[", self.expected_write_id)?;
+        for (index, info) in self.items.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{info}")?;
         }
+        write!(f, "]")
     }
 }
 
 impl<T> Queue<T> {
+    #[must_use]
+    pub const fn new(tick_id: TickId) -> Self {
+        Self::with_step(tick_id, 1)
+    }
+
+    /// Creates a queue where each pushed item advances the tick sequence by `step` ticks
+    /// instead of one, e.g. when only every fourth simulation tick is sampled.
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    #[must_use]
+    pub const fn with_step(tick_id: TickId, step: u32) -> Self {
+        assert!(step > 0, "tick step must be greater than zero");
+        Self {
+            items: VecDeque::new(),
+            expected_write_id: tick_id,
+            tick_step: step,
+            expected_read_id: tick_id,
+            #[cfg(feature = "runtime")]
+            waker: None,
+            #[cfg(feature = "runtime")]
+            on_discard: None,
+            #[cfg(feature = "runtime")]
+            arrivals: VecDeque::new(),
+            metrics: QueueMetrics::new(),
+            max_len: None,
+            auto_trim_target: None,
+            #[cfg(debug_assertions)]
+            monotonic_check: None,
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &ItemInfo<T>> {
         self.items.iter()
     }
+
+    /// Iterates the items newest first.
+    pub fn rev_iter(&self) -> impl Iterator<Item = &ItemInfo<T>> {
+        self.items.iter().rev()
+    }
+
+    /// Calls `visit` for every item from front to back, letting protocol code serialize straight
+    /// into its own buffer without an intermediate allocation.
+    pub fn encode_into<F: FnMut(&ItemInfo<T>)>(&self, mut visit: F) {
+        for item_info in &self.items {
+            visit(item_info);
+        }
+    }
+
+    /// Iterates the items with a tick strictly after `tick_id`, e.g. everything newer than the
+    /// last acknowledged tick.
+    pub fn iter_after(&self, tick_id: TickId) -> impl Iterator<Item = &ItemInfo<T>> {
+        let start = self
+            .items
+            .partition_point(|item_info| item_info.tick_id <= tick_id);
+        self.items.range(start..)
+    }
+
+    /// Iterates the ticks of the items in order, without their payloads.
+    pub fn tick_ids(&self) -> impl Iterator<Item = TickId> + '_ {
+        self.items.iter().map(|item_info| item_info.tick_id)
+    }
+
+    /// Iterates the items whose payload matches the predicate, keeping their ticks attached.
+    pub fn iter_filter<F: FnMut(&T) -> bool>(
+        &self,
+        mut f: F,
+    ) -> impl Iterator<Item = &ItemInfo<T>> {
+        self.items
+            .iter()
+            .filter(move |item_info| f(&item_info.item))
+    }
+
+    /// Iterates the items paired with their zero-based offset from the front.
+    ///
+    /// For a contiguous queue the offset is the number of steps between the item's tick and the front tick.
+    pub fn iter_offsets(&self) -> impl Iterator<Item = (usize, &ItemInfo<T>)> {
+        self.items.iter().enumerate()
+    }
+
+    /// Iterates the items oldest first, paired with their distance from the newest item.
+    ///
+    /// The back item has distance zero and the front item has distance `len() - 1`.
+    pub fn iter_from_back_distance(&self) -> impl Iterator<Item = (usize, &ItemInfo<T>)> {
+        let last_index = self.items.len().saturating_sub(1);
+        self.items
+            .iter()
+            .enumerate()
+            .map(move |(index, item_info)| (last_index - index, item_info))
+    }
+
+    /// Folds over the item payloads in tick order, without exposing the [`ItemInfo`] wrappers.
+    pub fn fold_items<B, F: FnMut(B, &T) -> B>(&self, init: B, mut f: F) -> B {
+        self.items
+            .iter()
+            .fold(init, |acc, item_info| f(acc, &item_info.item))
+    }
+
+    /// Returns the mean of the payloads, or `None` if the queue is empty, e.g. when each item is
+    /// a measured latency sample.
+    ///
+    /// The payload type must convert losslessly into `f64` through `Into<f64>`, which covers
+    /// `f32`, `f64` and the integer types up to 32 bits.
+    #[must_use]
+    pub fn average_payload(&self) -> Option<f64>
+    where
+        T: Into<f64> + Copy,
+    {
+        if self.items.is_empty() {
+            return None;
+        }
+        let sum = self.fold_items(0.0, |sum, item| sum + (*item).into());
+        Some(sum / self.items.len() as f64)
+    }
+
+    /// Returns the start tick and length of the longest run of consecutive items whose payload
+    /// satisfies `f`, e.g. the longest streak of held jump inputs. The first run wins a tie.
+    ///
+    /// Returns `None` if no item matches.
+    pub fn longest_run<F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<(TickId, usize)> {
+        let mut longest: Option<(TickId, usize)> = None;
+        let mut current: Option<(TickId, usize)> = None;
+        let mut previous_tick_id: Option<TickId> = None;
+        for item_info in &self.items {
+            let follows = previous_tick_id.is_some_and(|previous| {
+                previous.value().checked_add(self.tick_step) == Some(item_info.tick_id.value())
+            });
+            previous_tick_id = Some(item_info.tick_id);
+            if !f(&item_info.item) {
+                current = None;
+                continue;
+            }
+            let run = match current {
+                Some((start, length)) if follows => (start, length + 1),
+                _ => (item_info.tick_id, 1),
+            };
+            current = Some(run);
+            if longest.is_none_or(|(_, longest_length)| run.1 > longest_length) {
+                longest = Some(run);
+            }
+        }
+        longest
+    }
+
+    /// Iterates the contiguous prefix of the queue, stopping at the first tick discontinuity.
+    ///
+    /// With the contiguity invariant intact this yields every item, but consumers relying on an
+    /// unbroken sequence are protected should a gap ever make it into the queue.
+    pub fn iter_contiguous(&self) -> impl Iterator<Item = &ItemInfo<T>> {
+        let tick_step = self.tick_step;
+        let mut previous: Option<TickId> = None;
+        self.items.iter().take_while(move |item_info| {
+            let is_contiguous = previous.is_none_or(|previous| {
+                previous.value().checked_add(tick_step) == Some(item_info.tick_id.value())
+            });
+            previous = Some(item_info.tick_id);
+            is_contiguous
+        })
+    }
+
+    /// Consumes the queue and returns the internal deque together with the expected write tick.
+    #[must_use]
+    pub fn into_parts(self) -> (VecDeque<ItemInfo<T>>, TickId) {
+        (self.items, self.expected_write_id)
+    }
+
+    /// Consumes the queue and converts each payload with `f`, keeping ticks and the expected write tick.
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> Queue<U> {
+        Queue {
+            items: self
+                .items
+                .into_iter()
+                .map(|item_info| ItemInfo {
+                    item: f(item_info.item),
+                    tick_id: item_info.tick_id,
+                })
+                .collect(),
+            expected_read_id: self.expected_read_id,
+            #[cfg(feature = "runtime")]
+            waker: self.waker,
+            #[cfg(feature = "runtime")]
+            arrivals: self.arrivals,
+            metrics: self.metrics,
+            max_len: self.max_len,
+            auto_trim_target: self.auto_trim_target,
+            #[cfg(debug_assertions)]
+            monotonic_check: self.monotonic_check,
+            ..Queue::with_step(self.expected_write_id, self.tick_step)
+        }
+    }
+
+    /// Like [`Queue::map`], but borrows the queue and leaves it untouched.
+    pub fn map_ref<U, F: FnMut(&T) -> U>(&self, mut f: F) -> Queue<U> {
+        Queue {
+            items: self
+                .items
+                .iter()
+                .map(|item_info| ItemInfo {
+                    item: f(&item_info.item),
+                    tick_id: item_info.tick_id,
+                })
+                .collect(),
+            expected_read_id: self.expected_read_id,
+            #[cfg(feature = "runtime")]
+            arrivals: self.arrivals.clone(),
+            metrics: self.metrics,
+            max_len: self.max_len,
+            auto_trim_target: self.auto_trim_target,
+            #[cfg(debug_assertions)]
+            monotonic_check: self.monotonic_check,
+            ..Queue::with_step(self.expected_write_id, self.tick_step)
+        }
+    }
+
+    /// Returns the index of the first item matching the predicate.
+    pub fn position<F: FnMut(&ItemInfo<T>) -> bool>(&self, f: F) -> Option<usize> {
+        self.items.iter().position(f)
+    }
+
+    /// Returns the first item matching the predicate.
+    pub fn find<F: FnMut(&ItemInfo<T>) -> bool>(&self, mut f: F) -> Option<&ItemInfo<T>> {
+        self.items.iter().find(|item_info| f(item_info))
+    }
+
+    /// Returns `true` if the queue holds exactly the `(tick, payload)` pairs in `expected`, in
+    /// order. Handy for one-line test assertions.
+    #[must_use]
+    pub fn matches(&self, expected: &[(TickId, T)]) -> bool
+    where
+        T: PartialEq,
+    {
+        self.items.len() == expected.len()
+            && self
+                .items
+                .iter()
+                .zip(expected)
+                .all(|(item_info, (tick_id, item))| {
+                    item_info.tick_id == *tick_id && item_info.item == *item
+                })
+    }
+
+    /// Writes the queue in a compact binary form without pulling in serde: the front tick and the
+    /// item count as big-endian `u32`, followed by each payload written by `encode_item`.
+    ///
+    /// An empty queue writes its expected write tick as the front. See
+    /// [`Queue::decode_len_prefixed`] for the reverse, which always assumes a tick step of one.
+    ///
+    /// # Errors
+    /// Returns any error from writing to `w` or from `encode_item`, and
+    /// `std::io::ErrorKind::InvalidInput` if the queue holds more than `u32::MAX` items or its tick
+    /// step is not one. Nothing is written in that case.
+    pub fn encode_len_prefixed<W, F>(&self, w: &mut W, encode_item: F) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+        F: Fn(&T, &mut W) -> std::io::Result<()>,
+    {
+        if self.tick_step != 1 {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+        }
+        let front_tick_id = self
+            .items
+            .front()
+            .map_or(self.expected_write_id, |item_info| item_info.tick_id);
+        let count = u32::try_from(self.items.len())
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+        w.write_all(&front_tick_id.value().to_be_bytes())?;
+        w.write_all(&count.to_be_bytes())?;
+        for item_info in &self.items {
+            encode_item(&item_info.item, w)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a header with the expected write tick, then one `tick: payload` line per item,
+    /// straight into `w` without building a `String` first.
+    ///
+    /// # Errors
+    /// Returns any error from writing to `w`.
+    pub fn write_debug<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        T: Display,
+    {
+        writeln!(w, "Queue(expected_write={})", self.expected_write_id)?;
+        for item_info in &self.items {
+            writeln!(w, "{item_info}")?;
+        }
+        Ok(())
+    }
 }
 
 impl<T> IntoIterator for Queue<T> {
@@ -86,6 +497,18 @@ impl<T> IntoIterator for Queue<T> {
     }
 }
 
+impl<T> From<Queue<T>> for Vec<ItemInfo<T>> {
+    fn from(queue: Queue<T>) -> Self {
+        queue.items.into()
+    }
+}
+
+impl<T> From<Queue<T>> for VecDeque<ItemInfo<T>> {
+    fn from(queue: Queue<T>) -> Self {
+        queue.items
+    }
+}
+
 pub struct FromIndexIterator<'a, T> {
     deque: &'a VecDeque<ItemInfo<T>>,
     #[allow(unused)]
@@ -114,154 +537,1972 @@ impl<T: Clone> Iterator for FromIndexIterator<'_, T> {
     }
 }
 
+/// The deque is borrowed immutably and the index only grows, so once `next` returns `None`
+/// it keeps doing so.
+impl<T: Clone> FusedIterator for FromIndexIterator<'_, T> {}
+
 pub const TICK_ID_MAX: u32 = u32::MAX;
 
-#[derive(Debug)]
-pub enum QueueError {
-    WrongTickId {
-        expected: TickId,
-        encountered: TickId,
-    },
-}
+/// A 64-bit FNV-1a hasher with a fixed seed, so checksums are reproducible across runs.
+///
+/// Integers are fed in little-endian order and `usize` is widened to `u64`, so the result
+/// does not depend on the endianness or pointer width of the machine either.
+struct Fnv1aHasher(u64);
 
-impl<T: Clone> Queue<T> {
-    #[must_use]
-    pub const fn new(tick_id: TickId) -> Self {
-        Self {
-            items: VecDeque::new(),
-            expected_write_id: tick_id,
-        }
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
     }
+}
 
-    /// Clears the queue and resets the expected read and write tick IDs.
-    pub fn clear(&mut self, initial_tick_id: TickId) {
-        self.items.clear();
-        self.expected_write_id = initial_tick_id;
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
     }
 
-    /// Pushes an item into the queue at the specified `TickId`.
-    ///
-    /// This method ensures that the item is added at the correct position in the tick sequence. The
-    /// `tick_id` must match the expected `TickId` for the queue to maintain an unbroken sequence.
-    ///
-    /// # Parameters
-    /// - `tick_id`: The `TickId` where the item should be inserted. It must match the queue's expected next `TickId`.
-    /// - `item`: The item to be inserted into the queue.
-    ///
-    /// # Returns
-    /// - `Ok(())` if the item is successfully added to the queue.
-    /// - `Err(QueueError)` if the provided `tick_id` does not match the expected `TickId`.
-    ///
-    /// # Errors
-    /// - Returns a `QueueError::WrongTickId` if the `tick_id` provided does not match the expected
-    ///   `TickId`, which maintains the sequential order of the queue.
-    ///
-    pub fn push(&mut self, tick_id: TickId, item: T) -> Result<(), QueueError> {
-        if self.expected_write_id != tick_id {
-            Err(QueueError::WrongTickId {
-                expected: self.expected_write_id,
-                encountered: tick_id,
-            })?;
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
         }
+    }
 
-        self.push_internal(item);
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
 
-        Ok(())
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
     }
 
-    fn push_internal(&mut self, item: T) {
-        let info = ItemInfo {
-            item,
-            tick_id: self.expected_write_id,
-        };
-        self.items.push_back(info);
-        self.expected_write_id += 1;
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
     }
 
-    #[must_use]
-    pub fn debug_get(&self, index: usize) -> Option<&ItemInfo<T>> {
-        self.items.get(index)
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
     }
 
-    #[must_use]
-    pub fn pop(&mut self) -> Option<ItemInfo<T>> {
-        self.items.pop_front()
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
     }
+}
 
-    pub fn discard_up_to(&mut self, tick_id: TickId) {
-        while let Some(info) = self.items.front() {
-            if info.tick_id >= tick_id {
-                break;
-            }
+#[derive(Debug)]
+pub enum QueueError {
+    #[deprecated(note = "pushes now report `DuplicateTickId` or `GapDetected` instead")]
+    WrongTickId {
+        expected: TickId,
+        encountered: TickId,
+    },
+    /// A push at a tick that has already been written, e.g. a resent input.
+    DuplicateTickId {
+        tick_id: TickId,
+    },
+    /// A push ahead of the expected write tick, which would leave a gap.
+    GapDetected {
+        expected: TickId,
+        encountered: TickId,
+    },
+    UnexpectedReadTick {
+        expected: TickId,
+        encountered: TickId,
+    },
+    Empty,
+    TickNotPresent {
+        tick_id: TickId,
+    },
+    BrokenSequence {
+        at_index: usize,
+        expected: TickId,
+        encountered: TickId,
+    },
+    TickOverflow {
+        tick_id: TickId,
+        delta: i64,
+    },
+    SlotTaken {
+        tick_id: TickId,
+        slot: usize,
+    },
+    Full {
+        max_len: usize,
+    },
+}
 
-            self.items.pop_front();
-        }
+/// The changes that turn one queue into another, as computed by [`Queue::diff`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QueueDiff<T> {
+    /// Ticks that are no longer present.
+    pub removed: Vec<TickId>,
+    /// Ticks present in both queues whose payload changed.
+    pub changed: Vec<ItemInfo<T>>,
+    /// Ticks that were not present before.
+    pub added: Vec<ItemInfo<T>>,
+    pub expected_write_id: TickId,
+}
+
+impl<T> QueueDiff<T> {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty() && self.added.is_empty()
     }
+}
 
-    pub fn discard_count(&mut self, count: usize) {
-        if count >= self.items.len() {
-            self.items.clear();
-        } else {
-            self.items.drain(..count);
-        }
+/// The items and expected write tick of a queue, captured by [`Queue::snapshot`] as a
+/// rollback checkpoint.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QueueSnapshot<T> {
+    items: VecDeque<ItemInfo<T>>,
+    #[cfg(feature = "runtime")]
+    arrivals: VecDeque<Option<Instant>>,
+    expected_write_id: TickId,
+}
+
+/// Configures a [`Queue`] with several options at once. Use [`Queue::new`] for the simple case.
+pub struct QueueBuilder<T> {
+    initial_tick_id: TickId,
+    capacity: usize,
+    max_len: Option<usize>,
+    step: u32,
+    phantom: PhantomData<fn() -> T>, // Does not own a `T`, so no bounds on `T` for the impls below
+}
+
+impl<T> Debug for QueueBuilder<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueueBuilder")
+            .field("initial_tick_id", &self.initial_tick_id)
+            .field("capacity", &self.capacity)
+            .field("max_len", &self.max_len)
+            .field("step", &self.step)
+            .finish()
     }
+}
 
-    /// Pops up to a certain amount of items from the front of the queue and returns
-    /// the first `TickId` and a vector of `T`. Returns `None` if the queue
-    /// is empty.
-    ///
-    /// # Parameters
-    /// - `count`: The number of items to pop (or fewer if not enough items are available).
-    ///
-    /// # Returns
-    /// - `Some((TickId, Vec<T>))` if there are items available.
-    /// - `None` if the queue is empty.
-    ///
-    /// # Example
-    /// ```rust
-    /// use tick_id::TickId;
-    /// use tick_queue::Queue;
-    /// let mut items = Queue::new(TickId::new(0));
-    /// items.push(TickId::new(0), "Step 1").unwrap();
-    /// items.push(TickId::new(1), "Step 2").unwrap();
-    ///
-    /// let result = items.take(5);  // Will return up to 5 items (in this case 2)
-    /// if let Some((tick_id, popped_items)) = result {
-    ///     assert_eq!(tick_id, TickId::new(0));
-    ///     assert_eq!(popped_items, vec!["Step 1", "Step 2"]);
-    /// }
-    /// ```
-    #[must_use]
-    pub fn take(&mut self, count: usize) -> Option<(TickId, Vec<T>)> {
-        let first_tick_id = self.front_tick_id()?;
+impl<T> Clone for QueueBuilder<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
 
-        let items_to_take: Vec<T> = self
-            .items
-            .drain(..count.min(self.items.len()))
-            .map(|item_info| item_info.item)
-            .collect();
+impl<T> Copy for QueueBuilder<T> {}
 
-        Some((first_tick_id, items_to_take))
+impl<T> Default for QueueBuilder<T> {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
+impl<T> QueueBuilder<T> {
     #[must_use]
-    pub fn front_tick_id(&self) -> Option<TickId> {
-        self.items.front().map(|item_info| item_info.tick_id)
+    pub const fn new() -> Self {
+        Self {
+            initial_tick_id: TickId(0),
+            capacity: 0,
+            max_len: None,
+            step: 1,
+            phantom: PhantomData,
+        }
     }
 
+    /// The tick of the first item, zero by default.
     #[must_use]
-    pub const fn expected_write_tick_id(&self) -> TickId {
-        self.expected_write_id
+    pub const fn initial_tick(mut self, tick_id: TickId) -> Self {
+        self.initial_tick_id = tick_id;
+        self
     }
 
+    /// Reserves room for at least `capacity` items up front.
     #[must_use]
-    pub fn back_tick_id(&self) -> Option<TickId> {
-        self.items.back().map(|item_info| item_info.tick_id)
+    pub const fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
     }
 
+    /// See [`Queue::set_max_len`].
     #[must_use]
-    pub fn len(&self) -> usize {
-        self.items.len()
+    pub const fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// See [`Queue::with_step`].
+    #[must_use]
+    pub const fn step(mut self, step: u32) -> Self {
+        self.step = step;
+        self
+    }
+}
+
+impl<T: Clone> QueueBuilder<T> {
+    /// # Panics
+    /// Panics if the step is zero.
+    #[must_use]
+    pub fn build(self) -> Queue<T> {
+        let mut queue = Queue::with_step(self.initial_tick_id, self.step);
+        queue.items.reserve(self.capacity);
+        queue.set_max_len(self.max_len);
+        queue
+    }
+}
+
+impl<T: Clone> Queue<T> {
+    /// Creates an empty queue expecting its first push at `tick_id`. Same as [`Queue::new`], but
+    /// makes the starting tick obvious at call sites.
+    #[must_use]
+    pub const fn empty_at(tick_id: TickId) -> Self {
+        Self::new(tick_id)
+    }
+
+    /// Creates an empty queue with room for at least `capacity` items before reallocating.
+    #[must_use]
+    pub fn with_capacity(tick_id: TickId, capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            ..Self::new(tick_id)
+        }
+    }
+
+    /// Rebuilds a queue from a front tick and its payloads, e.g. the pair returned by [`Queue::take`].
+    ///
+    /// The first item is stored at `front_tick_id` and the expected write tick ends up just past
+    /// the last one. An empty `items` produces an empty queue seeded at `front_tick_id`.
+    ///
+    /// # Panics
+    /// Panics if the expected write tick would pass [`TICK_ID_MAX`].
+    #[must_use]
+    pub fn from_items(front_tick_id: TickId, items: Vec<T>) -> Self {
+        let mut queue = Self::with_capacity(front_tick_id, items.len());
+        for item in items {
+            queue.push_internal(item);
+        }
+        queue.debug_assert_invariants();
+        queue
+    }
+
+    /// Builds a queue from ordered `(tick, payload)` pairs, the counterpart of [`Queue::to_pairs`].
+    ///
+    /// The queue starts at `start_tick_id` with the given tick `step`, so pass the front tick (or
+    /// the expected write tick, if the queue was empty) and [`Queue::tick_step`] of the original.
+    /// The expected write tick ends up just past the last pair, or at `start_tick_id` without pairs.
+    ///
+    /// # Errors
+    /// Returns `QueueError::BrokenSequence` if the first pair is not at `start_tick_id` or the
+    /// ticks are not `step` apart.
+    ///
+    /// # Panics
+    /// Panics if `step` is zero.
+    pub fn from_pairs(
+        start_tick_id: TickId,
+        step: u32,
+        pairs: impl IntoIterator<Item = (TickId, T)>,
+    ) -> Result<Self, QueueError> {
+        let mut pairs = pairs.into_iter().peekable();
+        if let Some((tick_id, _)) = pairs.peek() {
+            if *tick_id != start_tick_id {
+                return Err(QueueError::BrokenSequence {
+                    at_index: 0,
+                    expected: start_tick_id,
+                    encountered: *tick_id,
+                });
+            }
+        }
+        let mut queue = Self::with_step(start_tick_id, step);
+        queue.set_contents(pairs.map(|(tick_id, item)| ItemInfo::new(tick_id, item)))?;
+        Ok(queue)
+    }
+
+    /// Reads a queue written by [`Queue::encode_len_prefixed`], decoding each payload with
+    /// `decode_item`.
+    ///
+    /// # Errors
+    /// Returns any error from reading `r` or from `decode_item`, and
+    /// `std::io::ErrorKind::InvalidData` if the header describes ticks past [`TICK_ID_MAX`].
+    pub fn decode_len_prefixed<R, F>(r: &mut R, mut decode_item: F) -> std::io::Result<Self>
+    where
+        R: std::io::Read,
+        F: FnMut(&mut R) -> std::io::Result<T>,
+    {
+        let mut word = [0; 4];
+        r.read_exact(&mut word)?;
+        let front_tick_id = TickId::new(u32::from_be_bytes(word));
+        r.read_exact(&mut word)?;
+        let count = u32::from_be_bytes(word);
+
+        let mut queue = Self::new(front_tick_id);
+        if count > queue.ticks_until_overflow() {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidData));
+        }
+        for _ in 0..count {
+            let item = decode_item(r)?;
+            queue.push_internal(item);
+        }
+        queue.debug_assert_invariants();
+        Ok(queue)
+    }
+
+    /// Rebuilds a contiguous queue from `(run length, payload)` runs, the counterpart of
+    /// [`Queue::run_length_encode`]. Each run is expanded into that many items; empty runs are
+    /// skipped.
+    ///
+    /// # Panics
+    /// Panics if the expected write tick would pass [`TICK_ID_MAX`].
+    #[must_use]
+    pub fn from_run_length(start_tick_id: TickId, runs: &[(usize, T)]) -> Self {
+        let mut queue = Self::with_capacity(start_tick_id, runs.iter().map(|run| run.0).sum());
+        for (length, item) in runs {
+            for _ in 0..*length {
+                queue.push_internal(item.clone());
+            }
+        }
+        queue.debug_assert_invariants();
+        queue
+    }
+
+    /// Creates a queue directly from its internal parts, the counterpart of [`Queue::into_parts`].
+    ///
+    /// The items must be contiguous and `expected_write_id` must follow the back item.
+    /// This is checked by debug assertions only.
+    #[must_use]
+    pub fn from_parts(items: VecDeque<ItemInfo<T>>, expected_write_id: TickId) -> Self {
+        let expected_read_id = items
+            .front()
+            .map_or(expected_write_id, |item_info| item_info.tick_id);
+        let mut queue = Self {
+            items,
+            expected_read_id,
+            ..Self::new(expected_write_id)
+        };
+        queue.record_len();
+        queue.debug_assert_invariants();
+        queue
+    }
+
+    /// Creates a queue from raw parts without any validation, not even debug assertions.
+    ///
+    /// Only meant for tests that need malformed queues (gaps, wrong expected write tick) to
+    /// exercise error paths such as [`Queue::verify`] that the regular API can not reach.
+    #[cfg(any(test, feature = "testing"))]
+    #[must_use]
+    pub fn from_raw_unchecked(items: VecDeque<ItemInfo<T>>, expected_write_id: TickId) -> Self {
+        let expected_read_id = items
+            .front()
+            .map_or(expected_write_id, |item_info| item_info.tick_id);
+        Self {
+            items,
+            expected_read_id,
+            ..Self::new(expected_write_id)
+        }
+    }
+
+    /// Clears the queue and resets the expected read and write tick IDs.
+    ///
+    /// The removed items count as discarded and reach the [`Queue::set_on_discard`] callback.
+    /// The [`Queue::metrics`] are kept; use [`Queue::reset_metrics`] to zero them.
+    pub fn clear(&mut self, initial_tick_id: TickId) {
+        self.discard_front(self.items.len());
+        #[cfg(feature = "runtime")]
+        self.arrivals.clear();
+        self.expected_write_id = initial_tick_id;
+        self.expected_read_id = initial_tick_id;
+        self.debug_assert_invariants();
+    }
+
+    /// Returns the cumulative push, pop and discard counters since creation or the last
+    /// [`Queue::reset_metrics`].
+    #[must_use]
+    pub const fn metrics(&self) -> &QueueMetrics {
+        &self.metrics
+    }
+
+    /// Zeroes the [`Queue::metrics`]. The peak length restarts from the current length.
+    pub fn reset_metrics(&mut self) {
+        self.metrics = QueueMetrics::new();
+        self.record_len();
+    }
+
+    /// Clears the queue and reseeds it at `reset_tick` if [`Queue::verify`] finds a broken
+    /// sequence, e.g. after receiving corrupt data through [`Queue::from_parts`].
+    ///
+    /// Unlike [`Queue::verify`], whole-step gaps as left by [`Queue::push_skipping`] are accepted,
+    /// so a lossy queue is not wiped for skipping ahead.
+    ///
+    /// Returns `true` if the queue was reset, and `false` if it was healthy and left untouched.
+    pub fn clear_if_broken(&mut self, reset_tick: TickId) -> bool {
+        if self.check_sequence(true).is_ok() {
+            return false;
+        }
+        self.clear(reset_tick);
+        true
+    }
+
+    /// Clears the queue and seeds it with a contiguous run of items starting at `initial_tick_id`.
+    ///
+    /// Afterwards the expected write tick points just past the last seeded item.
+    ///
+    /// # Panics
+    /// Panics if the expected write tick would pass [`TICK_ID_MAX`].
+    pub fn reset_with(&mut self, initial_tick_id: TickId, items: impl IntoIterator<Item = T>) {
+        self.clear(initial_tick_id);
+        for item in items {
+            self.push_internal(item);
+        }
+        self.debug_assert_invariants();
+    }
+
+    /// Replaces the whole content with `items`, e.g. for a full-state sync.
+    ///
+    /// The items must be contiguous; afterwards the expected write tick points just past the last
+    /// one. An empty `items` clears the queue but keeps the current expected write tick.
+    /// The replaced items count as discarded.
+    ///
+    /// # Errors
+    /// - Returns [`QueueError::BrokenSequence`] on the first gap.
+    /// - Returns [`QueueError::TickOverflow`] if a tick, including the expected write tick after
+    ///   the last item, would pass `u32::MAX`.
+    ///
+    /// The queue is left untouched when an error is returned.
+    pub fn set_contents(
+        &mut self,
+        items: impl IntoIterator<Item = ItemInfo<T>>,
+    ) -> Result<(), QueueError> {
+        let items: VecDeque<_> = items.into_iter().collect();
+        let mut expected_write_id = self.expected_write_id;
+        for (index, item_info) in items.iter().enumerate() {
+            if index > 0 && item_info.tick_id != expected_write_id {
+                return Err(QueueError::BrokenSequence {
+                    at_index: index,
+                    expected: expected_write_id,
+                    encountered: item_info.tick_id,
+                });
+            }
+            expected_write_id = item_info
+                .tick_id
+                .value()
+                .checked_add(self.tick_step)
+                .map(TickId::new)
+                .ok_or(QueueError::TickOverflow {
+                    tick_id: item_info.tick_id,
+                    delta: i64::from(self.tick_step),
+                })?;
+        }
+
+        #[cfg(feature = "runtime")]
+        let was_empty = self.items.is_empty();
+        self.discard_front(self.items.len());
+        self.expected_read_id = items
+            .front()
+            .map_or(expected_write_id, |item_info| item_info.tick_id);
+        self.expected_write_id = expected_write_id;
+        self.items = items;
+        #[cfg(feature = "runtime")]
+        self.arrivals.clear();
+        #[cfg(feature = "runtime")]
+        self.wake_if_filled(was_empty);
+        self.record_len();
+        self.debug_assert_invariants();
+        Ok(())
+    }
+
+    /// Pushes an item into the queue at the specified `TickId`.
+    ///
+    /// This method ensures that the item is added at the correct position in the tick sequence. The
+    /// `tick_id` must match the expected `TickId` for the queue to maintain an unbroken sequence.
+    ///
+    /// # Parameters
+    /// - `tick_id`: The `TickId` where the item should be inserted. It must match the queue's expected next `TickId`.
+    /// - `item`: The item to be inserted into the queue.
+    ///
+    /// # Returns
+    /// - `Ok(TickId)` with the tick the item was written at, which always equals `tick_id`.
+    /// - `Err(QueueError)` if the provided `tick_id` does not match the expected `TickId`.
+    ///
+    /// # Errors
+    /// - Returns a `QueueError::DuplicateTickId` if `tick_id` has already been written, e.g. a resent
+    ///   input.
+    /// - Returns a `QueueError::GapDetected` if `tick_id` is ahead of the expected `TickId`, which
+    ///   would break the sequential order of the queue.
+    /// - Returns a `QueueError::Full` if a maximum length is set and already reached.
+    /// - Returns a `QueueError::TickOverflow` if the expected write tick would pass [`TICK_ID_MAX`].
+    ///
+    pub fn push(&mut self, tick_id: TickId, item: T) -> Result<TickId, QueueError> {
+        self.check_write_tick_id(tick_id)?;
+        self.check_room(1)?;
+
+        self.push_internal(item);
+        self.debug_assert_invariants();
+
+        Ok(tick_id)
+    }
+
+    /// Like [`Queue::push`], but records `instant` as the arrival time of the item, readable
+    /// through [`Queue::arrival_instant`] and [`Queue::oldest_age`].
+    ///
+    /// # Errors
+    /// Same as [`Queue::push`].
+    #[cfg(feature = "runtime")]
+    pub fn push_at(
+        &mut self,
+        tick_id: TickId,
+        item: T,
+        instant: Instant,
+    ) -> Result<TickId, QueueError> {
+        self.check_write_tick_id(tick_id)?;
+        self.check_room(1)?;
+
+        self.push_internal(item);
+        self.set_back_arrival(Some(instant));
+        self.debug_assert_invariants();
+
+        Ok(tick_id)
+    }
+
+    /// Returns how long the front item has been buffered at `now`.
+    ///
+    /// Returns `None` if the queue is empty or the front item was not pushed with
+    /// [`Queue::push_at`].
+    #[cfg(feature = "runtime")]
+    #[must_use]
+    pub fn oldest_age(&self, now: Instant) -> Option<Duration> {
+        let arrival = (*self.arrivals.front()?)?;
+        Some(now.saturating_duration_since(arrival))
+    }
+
+    /// Returns when the item at `tick_id` was pushed, if it is buffered and was pushed with
+    /// [`Queue::push_at`].
+    #[cfg(feature = "runtime")]
+    #[must_use]
+    pub fn arrival_instant(&self, tick_id: TickId) -> Option<Instant> {
+        let index = self.offset_from_front(tick_id)?;
+        self.arrivals.get(index).copied().flatten()
+    }
+
+    /// Records the arrival of the back item, starting to track arrivals on first use.
+    #[cfg(feature = "runtime")]
+    fn set_back_arrival(&mut self, arrival: Option<Instant>) {
+        if arrival.is_none() && self.arrivals.is_empty() {
+            return;
+        }
+        self.arrivals.resize(self.items.len(), None);
+        if let Some(back) = self.arrivals.back_mut() {
+            *back = arrival;
+        }
+    }
+
+    /// Pushes an item at `tick_id` even if it is ahead of the expected write tick, for lossy
+    /// transports where the missing ticks will never arrive. Returns how many steps were skipped,
+    /// which is zero for an in-order push.
+    ///
+    /// Skipping ahead on a non-empty queue intentionally leaves a gap in the tick sequence.
+    /// Tick lookups such as [`Queue::get_by_tick_id`] still resolve against the ticks actually
+    /// stored, but [`Queue::verify`] will report the gap as a broken sequence.
+    /// [`Queue::clear_if_broken`] accepts such gaps.
+    ///
+    /// # Errors
+    /// - Returns a `QueueError::DuplicateTickId` if `tick_id` is older than the expected write tick.
+    /// - Returns a `QueueError::GapDetected` if `tick_id` is not a whole number of steps ahead of it.
+    /// - Returns a `QueueError::Full` if a maximum length is set and already reached.
+    /// - Returns a `QueueError::TickOverflow` if the expected write tick would pass [`TICK_ID_MAX`].
+    pub fn push_skipping(&mut self, tick_id: TickId, item: T) -> Result<u32, QueueError> {
+        let delta = tick_id
+            .value()
+            .checked_sub(self.expected_write_id.value())
+            .ok_or(QueueError::DuplicateTickId { tick_id })?;
+        if delta % self.tick_step != 0 {
+            return Err(QueueError::GapDetected {
+                expected: self.expected_write_id,
+                encountered: tick_id,
+            });
+        }
+        if tick_id.value().checked_add(self.tick_step).is_none() {
+            return Err(QueueError::TickOverflow {
+                tick_id,
+                delta: i64::from(self.tick_step),
+            });
+        }
+        self.check_room(1)?;
+
+        self.expected_write_id = tick_id;
+        self.push_internal(item);
+        self.debug_assert_invariants();
+
+        Ok(delta / self.tick_step)
+    }
+
+    /// Pushes an item like [`Queue::push`] and returns a mutable reference to the inserted entry.
+    ///
+    /// # Errors
+    /// - Same as [`Queue::push`]. Nothing is inserted in that case.
+    pub fn push_and_get_mut(
+        &mut self,
+        tick_id: TickId,
+        item: T,
+    ) -> Result<&mut ItemInfo<T>, QueueError> {
+        self.push(tick_id, item)?;
+
+        Ok(self
+            .items
+            .back_mut()
+            .expect("queue can not be empty after a push"))
+    }
+
+    /// Pushes a contiguous run of items starting at `start_tick_id` with all-or-nothing semantics.
+    ///
+    /// The slice is contiguous by construction, so only the start tick needs to be validated.
+    /// The queue is left untouched if it is rejected.
+    ///
+    /// # Errors
+    /// - Returns a `QueueError::DuplicateTickId` or `QueueError::GapDetected` if `start_tick_id`
+    ///   does not match the expected `TickId`.
+    /// - Returns a `QueueError::Full` if the items do not all fit below the maximum length.
+    /// - Returns a `QueueError::TickOverflow` if the expected write tick would pass [`TICK_ID_MAX`].
+    pub fn push_slice(&mut self, start_tick_id: TickId, items: &[T]) -> Result<(), QueueError> {
+        self.check_write_tick_id(start_tick_id)?;
+        self.check_room(items.len())?;
+
+        self.items.reserve(items.len());
+        for item in items {
+            self.push_internal(item.clone());
+        }
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    /// Replaces the payloads of the already buffered ticks `start_tick_id..start_tick_id + items.len()`,
+    /// e.g. when applying an authoritative correction on top of predicted inputs.
+    ///
+    /// The ticks and the length of the queue are unchanged.
+    ///
+    /// # Errors
+    /// - Returns `QueueError::TickNotPresent` with the first missing tick if any part of the range is
+    ///   not buffered. Nothing is overwritten in that case.
+    pub fn overwrite_range(
+        &mut self,
+        start_tick_id: TickId,
+        items: &[T],
+    ) -> Result<(), QueueError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let start_index =
+            self.offset_from_front(start_tick_id)
+                .ok_or(QueueError::TickNotPresent {
+                    tick_id: start_tick_id,
+                })?;
+        let mut expected = start_tick_id.value();
+        for offset in 0..items.len() {
+            let present = self
+                .items
+                .get(start_index + offset)
+                .is_some_and(|item_info| item_info.tick_id.value() == expected);
+            if !present {
+                return Err(QueueError::TickNotPresent {
+                    tick_id: TickId::new(expected),
+                });
+            }
+            expected = expected.saturating_add(self.tick_step);
+        }
+
+        for (item_info, item) in self.items.range_mut(start_index..).zip(items) {
+            item_info.item = item.clone();
+        }
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    /// Clones all items of `other` onto the back of this queue, leaving `other` untouched.
+    ///
+    /// # Errors
+    /// - Returns a `QueueError::DuplicateTickId` or `QueueError::GapDetected` if `other` does not
+    ///   start at the expected write tick.
+    ///   Nothing is modified in that case.
+    /// - Returns a `QueueError::Full` if the items do not all fit below the maximum length.
+    /// - Returns a `QueueError::TickOverflow` if the expected write tick would pass [`TICK_ID_MAX`].
+    pub fn extend_from_queue(&mut self, other: &Self) -> Result<(), QueueError> {
+        let Some(other_front_tick_id) = other.front_tick_id() else {
+            return Ok(());
+        };
+        self.check_write_tick_id(other_front_tick_id)?;
+        self.check_room(other.items.len())?;
+
+        self.items.reserve(other.items.len());
+        for item_info in &other.items {
+            self.push_internal(item_info.item.clone());
+        }
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    /// Merges `other` into this queue. Ticks present in both queues get the payload from `other`,
+    /// and ticks past the current back are appended.
+    ///
+    /// Ticks in `other` that are older than this queue's front have already been consumed and are
+    /// ignored.
+    ///
+    /// # Errors
+    /// - Returns a `QueueError::GapDetected` if `other` starts after the expected write tick, since
+    ///   merging would leave a gap. Nothing is modified in that case.
+    /// - Returns a `QueueError::Full` if the appended items do not all fit below the maximum
+    ///   length.
+    /// - Returns a `QueueError::TickOverflow` if the expected write tick would pass [`TICK_ID_MAX`].
+    pub fn merge_overwrite(&mut self, other: &Self) -> Result<(), QueueError> {
+        let Some(other_front_tick_id) = other.front_tick_id() else {
+            return Ok(());
+        };
+        if other_front_tick_id > self.expected_write_id {
+            return Err(QueueError::GapDetected {
+                expected: self.expected_write_id,
+                encountered: other_front_tick_id,
+            });
+        }
+        self.check_room(
+            other
+                .items
+                .iter()
+                .filter(|item_info| item_info.tick_id >= self.expected_write_id)
+                .count(),
+        )?;
+
+        for item_info in &other.items {
+            if let Some(index) = self.offset_from_front(item_info.tick_id) {
+                self.items[index].item = item_info.item.clone();
+            } else if item_info.tick_id == self.expected_write_id {
+                self.push_internal(item_info.item.clone());
+            }
+        }
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    fn check_write_tick_id(&self, tick_id: TickId) -> Result<(), QueueError> {
+        if tick_id < self.expected_write_id {
+            Err(QueueError::DuplicateTickId { tick_id })?;
+        }
+        if tick_id > self.expected_write_id {
+            Err(QueueError::GapDetected {
+                expected: self.expected_write_id,
+                encountered: tick_id,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `additional` items fit below the maximum length and within the tick space.
+    fn check_room(&self, additional: usize) -> Result<(), QueueError> {
+        if u32::try_from(additional).map_or(true, |count| count > self.ticks_until_overflow()) {
+            return Err(QueueError::TickOverflow {
+                tick_id: self.expected_write_id,
+                delta: i64::try_from(additional)
+                    .unwrap_or(i64::MAX)
+                    .saturating_mul(i64::from(self.tick_step)),
+            });
+        }
+        let mut len_after = self.items.len() + additional;
+        if let Some(target) = self.auto_trim_target {
+            len_after = len_after.min(target.max(1));
+        }
+        match self.max_len {
+            Some(max_len) if len_after > max_len => Err(QueueError::Full { max_len }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the maximum number of items the queue may hold, or `None` for no limit.
+    ///
+    /// Pushes that would exceed it fail with `QueueError::Full`. Items already buffered beyond a
+    /// lowered limit are kept.
+    pub const fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+    }
+
+    #[must_use]
+    pub const fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Sets a target length that turns the queue into a rolling window: a push that would exceed
+    /// it first discards the oldest items. `None` disables trimming.
+    ///
+    /// The trimmed items go through the [`Queue::set_on_discard`] callback and are counted as
+    /// discarded in the [`Queue::metrics`].
+    pub const fn set_auto_trim_target(&mut self, target: Option<usize>) {
+        self.auto_trim_target = target;
+    }
+
+    #[must_use]
+    pub const fn auto_trim_target(&self) -> Option<usize> {
+        self.auto_trim_target
+    }
+
+    /// Returns how many more items can be pushed before reaching the maximum length, or `None`
+    /// if the queue is unbounded.
+    #[must_use]
+    pub fn remaining_capacity(&self) -> Option<usize> {
+        self.max_len
+            .map(|max_len| max_len.saturating_sub(self.items.len()))
+    }
+
+    fn push_internal(&mut self, item: T) {
+        self.append(item);
+        self.metrics.pushed += 1;
+    }
+
+    /// Appends at the expected write tick without counting a push, see [`Queue::requeue_front`].
+    fn append(&mut self, item: T) {
+        assert!(
+            self.ticks_until_overflow() > 0,
+            "expected write tick would pass TICK_ID_MAX"
+        );
+        if let Some(target) = self.auto_trim_target {
+            let excess = (self.items.len() + 1).saturating_sub(target.max(1));
+            self.discard_front(excess);
+        }
+
+        #[cfg(feature = "runtime")]
+        let was_empty = self.items.is_empty();
+
+        self.items
+            .push_back(ItemInfo::new(self.expected_write_id, item));
+        #[cfg(feature = "runtime")]
+        if !self.arrivals.is_empty() {
+            self.arrivals.push_back(None);
+        }
+        self.expected_write_id += self.tick_step;
+        self.record_len();
+
+        #[cfg(feature = "runtime")]
+        self.wake_if_filled(was_empty);
+    }
+
+    /// Wakes the registered waker if the queue was empty before and holds items now.
+    #[cfg(feature = "runtime")]
+    fn wake_if_filled(&self, was_empty: bool) {
+        if was_empty && !self.items.is_empty() {
+            if let Some(waker) = &self.waker {
+                waker.wake_by_ref();
+            }
+        }
+    }
+
+    /// Sets a callback that receives every item removed by [`Queue::discard_up_to`],
+    /// [`Queue::discard_count`], [`Queue::cap_to_newest`] and the other discarding methods,
+    /// e.g. to release handles or GPU buffers owned by the payload. Items replaced or dropped by
+    /// [`Queue::clear`], [`Queue::reset_with`], [`Queue::set_contents`], [`Queue::restore`] and
+    /// [`Queue::apply_diff`] reach it too.
+    ///
+    /// Items removed by [`Queue::pop`] or [`Queue::take`] are handed to the caller instead and do
+    /// not reach the callback. Replaces any previously set callback.
+    #[cfg(feature = "runtime")]
+    pub fn set_on_discard<F: FnMut(ItemInfo<T>) + Send + 'static>(&mut self, f: F) {
+        self.on_discard = Some(Box::new(f));
+    }
+
+    /// Registers a waker that is woken every time the queue goes from empty to non-empty, whether
+    /// through a push or through [`Queue::set_contents`], [`Queue::restore`] or
+    /// [`Queue::apply_diff`].
+    ///
+    /// Replaces any previously registered waker. This is the building block for driving
+    /// the queue from an async executor without polling.
+    #[cfg(feature = "runtime")]
+    pub fn register_waker(&mut self, waker: Waker) {
+        self.waker = Some(waker);
+    }
+
+    /// Checks that the stored ticks are increasing in whole steps and that the expected write
+    /// tick is just past the back item. Compiled out in release builds.
+    ///
+    /// Gaps are tolerated since [`Queue::push_skipping`] creates them on purpose; use
+    /// [`Queue::verify`] for the strict contiguity check.
+    fn debug_assert_invariants(&self) {
+        #[cfg(debug_assertions)]
+        {
+            debug_assert!(self.items.len() <= self.items.capacity());
+            #[cfg(feature = "runtime")]
+            debug_assert!(
+                self.arrivals.is_empty() || self.arrivals.len() == self.items.len(),
+                "arrival instants must stay parallel to the items"
+            );
+
+            let mut previous: Option<TickId> = None;
+            for item_info in &self.items {
+                if let Some(previous) = previous {
+                    debug_assert!(
+                        item_info.tick_id > previous
+                            && (item_info.tick_id.value() - previous.value()) % self.tick_step == 0,
+                        "ticks must increase in whole steps: {previous} followed by {}",
+                        item_info.tick_id
+                    );
+                }
+                previous = Some(item_info.tick_id);
+            }
+
+            if let Some(back_tick_id) = previous {
+                debug_assert_eq!(
+                    u64::from(self.expected_write_id.value()),
+                    u64::from(back_tick_id.value()) + u64::from(self.tick_step),
+                    "expected write tick must follow the back item"
+                );
+            }
+        }
+    }
+
+    /// Checks the internal consistency of the queue: every stored tick must be exactly one step
+    /// after its predecessor, and the expected write tick must be one step after the back item.
+    ///
+    /// Useful after [`Queue::from_parts`], where the caller supplies the raw content.
+    /// An empty queue is always consistent.
+    ///
+    /// # Errors
+    /// - Returns `QueueError::BrokenSequence` for the first violation found. If the items are
+    ///   contiguous but the expected write tick is off, `at_index` equals `len()`.
+    /// - Returns `QueueError::TickOverflow` if a tick one step after a stored item would pass
+    ///   `u32::MAX`, so no valid successor exists.
+    ///
+    /// Gaps left on purpose by [`Queue::push_skipping`] are reported as broken sequences too.
+    pub fn verify(&self) -> Result<(), QueueError> {
+        self.check_sequence(false)
+    }
+
+    /// Shared by [`Queue::verify`] and [`Queue::clear_if_broken`]. With `allow_skips`, a tick may
+    /// also be any whole number of steps after its predecessor.
+    fn check_sequence(&self, allow_skips: bool) -> Result<(), QueueError> {
+        let Some(mut expected) = self.front_tick_id() else {
+            return Ok(());
+        };
+
+        for (index, item_info) in self.items.iter().enumerate() {
+            let skipped_ahead = allow_skips
+                && item_info.tick_id > expected
+                && (item_info.tick_id.value() - expected.value()) % self.tick_step == 0;
+            if item_info.tick_id != expected && !skipped_ahead {
+                return Err(QueueError::BrokenSequence {
+                    at_index: index,
+                    expected,
+                    encountered: item_info.tick_id,
+                });
+            }
+            expected = item_info
+                .tick_id
+                .value()
+                .checked_add(self.tick_step)
+                .map(TickId::new)
+                .ok_or(QueueError::TickOverflow {
+                    tick_id: item_info.tick_id,
+                    delta: i64::from(self.tick_step),
+                })?;
+        }
+
+        if self.expected_write_id != expected {
+            return Err(QueueError::BrokenSequence {
+                at_index: self.items.len(),
+                expected,
+                encountered: self.expected_write_id,
+            });
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn debug_get(&self, index: usize) -> Option<&ItemInfo<T>> {
+        self.items.get(index)
+    }
+
+    /// Returns the index of the item stored at `tick_id`, taking the tick step into account.
+    ///
+    /// Returns `None` if the tick is outside the buffered range or does not land on a step boundary.
+    #[must_use]
+    pub fn index_of_tick_id(&self, tick_id: TickId) -> Option<usize> {
+        self.offset_from_front(tick_id)
+    }
+
+    /// Maps `tick_id` to a slot index relative to the front item.
+    ///
+    /// All tick-keyed lookups go through here, so the arithmetic is checked in one place and
+    /// never underflows for ticks below the front or overflows for ticks past the back.
+    ///
+    /// The computed slot is checked against the tick actually stored there. If they differ, the
+    /// sequence has a gap (see [`Queue::push_skipping`]) and the slot is found by binary search.
+    fn offset_from_front(&self, tick_id: TickId) -> Option<usize> {
+        let front_tick_id = self.front_tick_id()?;
+        let delta = tick_id.value().checked_sub(front_tick_id.value())?;
+        if delta % self.tick_step == 0 {
+            let index = usize::try_from(delta / self.tick_step).ok()?;
+            if self
+                .items
+                .get(index)
+                .is_some_and(|item_info| item_info.tick_id == tick_id)
+            {
+                return Some(index);
+            }
+        }
+
+        if self.back_tick_id()? - front_tick_id == self.span_of(self.items.len()) {
+            return None;
+        }
+        self.binary_search_tick_id(tick_id).ok()
+    }
+
+    /// The number of ticks covered by `count` contiguous items, minus one step.
+    fn span_of(&self, count: usize) -> i64 {
+        (count as i64 - 1) * i64::from(self.tick_step)
+    }
+
+    /// Returns `true` if any buffered payload equals `item`.
+    ///
+    /// This is a linear O(n) scan over the payloads, unlike the O(1) tick lookup
+    /// done by [`Queue::index_of_tick_id`].
+    #[must_use]
+    pub fn contains_item(&self, item: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.items.iter().any(|item_info| item_info.item == *item)
+    }
+
+    /// Binary searches the queue for `tick_id`, mirroring [`slice::binary_search`].
+    ///
+    /// Returns `Ok(index)` if the tick is stored, otherwise `Err(index)` where an item with
+    /// that tick would have to be inserted to keep the ticks ordered.
+    ///
+    /// # Errors
+    /// - Returns `Err(insertion_index)` if no item is stored at `tick_id`.
+    pub fn binary_search_tick_id(&self, tick_id: TickId) -> Result<usize, usize> {
+        self.items
+            .binary_search_by_key(&tick_id, |item_info| item_info.tick_id)
+    }
+
+    #[must_use]
+    pub fn get_by_tick_id(&self, tick_id: TickId) -> Option<&ItemInfo<T>> {
+        self.items.get(self.index_of_tick_id(tick_id)?)
+    }
+
+    #[must_use]
+    pub const fn tick_step(&self) -> u32 {
+        self.tick_step
+    }
+
+    /// Returns the item at the read position and moves the read position past it.
+    ///
+    /// The read position is the front unless it was moved by [`Queue::seek_read_to`] or
+    /// [`Queue::rotate_read_cursor`]. An item at the front is removed; an item past the front is
+    /// cloned and stays buffered, so moving the read position never drops items.
+    #[must_use]
+    pub fn pop(&mut self) -> Option<ItemInfo<T>> {
+        let mut popped = None;
+        self.read_from_cursor(1, |item_info| popped = Some(item_info));
+        popped
+    }
+
+    /// Pops and returns the payload if `tick_id` is next in line, and otherwise returns `None`
+    /// without modifying the queue. Unlike [`Queue::pop_expected`] a mismatch is not an error.
+    #[must_use]
+    pub fn take_if_front(&mut self, tick_id: TickId) -> Option<T> {
+        if self.next_pop_tick_id() != Some(tick_id) {
+            return None;
+        }
+        self.pop().map(|item_info| item_info.item)
+    }
+
+    /// Moves the front item to the back, e.g. to deprioritize it, and returns the tick it was
+    /// reassigned to, or `None` if the queue is empty or the expected write tick would pass
+    /// [`TICK_ID_MAX`]. The queue is left untouched when `None` is returned.
+    ///
+    /// The payload is pushed again at the expected write tick, so the queue stays gapless but the
+    /// original tick of the item is lost. The move counts as neither a pop nor a push in
+    /// [`Queue::metrics`].
+    pub fn requeue_front(&mut self) -> Option<TickId> {
+        if self.ticks_until_overflow() == 0 {
+            return None;
+        }
+        let front = self.items.pop_front()?;
+        #[cfg(feature = "runtime")]
+        let arrival = self.arrivals.pop_front().flatten();
+        self.advance_read_past(front.tick_id);
+        let tick_id = self.expected_write_id;
+        self.append(front.item);
+        #[cfg(feature = "runtime")]
+        self.set_back_arrival(arrival);
+        self.debug_assert_invariants();
+        Some(tick_id)
+    }
+
+    /// Discards every item older than `tick_id`, then pops the next one, whose tick is at least
+    /// `tick_id`. Lets a consumer that fell behind skip to the current tick in one call.
+    ///
+    /// Returns `None` if nothing at or after `tick_id` is buffered.
+    #[must_use]
+    pub fn pop_at_least(&mut self, tick_id: TickId) -> Option<ItemInfo<T>> {
+        self.advance_front_to(tick_id);
+        self.pop()
+    }
+
+    /// Pops items from the front for as long as the predicate accepts them, stopping at the first
+    /// rejected item (which stays in the queue) or when the queue runs empty.
+    pub fn pop_while<F: FnMut(&ItemInfo<T>) -> bool>(&mut self, mut f: F) -> Vec<ItemInfo<T>> {
+        let mut popped = Vec::new();
+        while self.peek_at_read_offset(0).is_some_and(&mut f) {
+            popped.extend(self.pop());
+        }
+        popped
+    }
+
+    /// Pops the item at the read position only if it is stored at `expected_tick_id`.
+    ///
+    /// Turns a silently desynchronized consumer into an explicit error.
+    ///
+    /// # Errors
+    /// - Returns `QueueError::UnexpectedReadTick` if the next item to pop has a different tick.
+    /// - Returns `QueueError::Empty` if there is nothing to pop.
+    ///
+    /// Nothing is removed when an error is returned.
+    pub fn pop_expected(&mut self, expected_tick_id: TickId) -> Result<ItemInfo<T>, QueueError> {
+        let next_tick_id = self.next_pop_tick_id().ok_or(QueueError::Empty)?;
+        if next_tick_id != expected_tick_id {
+            return Err(QueueError::UnexpectedReadTick {
+                expected: expected_tick_id,
+                encountered: next_tick_id,
+            });
+        }
+
+        self.pop().ok_or(QueueError::Empty)
+    }
+
+    pub fn discard_up_to(&mut self, tick_id: TickId) {
+        let _ = self.advance_front_to(tick_id);
+    }
+
+    /// Same as [`Queue::discard_up_to`], but returns how many items were dropped.
+    pub fn discard_up_to_counting(&mut self, tick_id: TickId) -> usize {
+        self.advance_front_to(tick_id)
+    }
+
+    /// Discards every item older than `tick_id` and returns how many were dropped, advancing the
+    /// expected read tick past them.
+    ///
+    /// A `tick_id` at or below the front drops nothing, and one past the back empties the queue.
+    pub fn advance_front_to(&mut self, tick_id: TickId) -> usize {
+        let count = match self.offset_from_front(tick_id) {
+            Some(index) => index,
+            None => self
+                .items
+                .iter()
+                .take_while(|info| info.tick_id < tick_id)
+                .count(),
+        };
+        self.discard_front(count)
+    }
+
+    /// Removes and returns every item matching the predicate, keeping their original ticks.
+    ///
+    /// Removing items from the middle would leave gaps, so the surviving items are renumbered to
+    /// stay contiguous from the original front tick, and the expected write tick is pulled back
+    /// accordingly. Survivors may therefore end up with different ticks than before.
+    pub fn extract_if<F: FnMut(&ItemInfo<T>) -> bool>(&mut self, mut f: F) -> Vec<ItemInfo<T>> {
+        let Some(front_tick_id) = self.front_tick_id() else {
+            return Vec::new();
+        };
+
+        let mut extracted = Vec::new();
+        let mut survivors = VecDeque::with_capacity(self.items.len());
+        #[cfg(feature = "runtime")]
+        let mut surviving_arrivals = VecDeque::with_capacity(self.arrivals.len());
+        #[cfg(feature = "runtime")]
+        let mut arrivals = self.arrivals.drain(..);
+        for item_info in self.items.drain(..) {
+            #[cfg(feature = "runtime")]
+            let arrival = arrivals.next();
+            if f(&item_info) {
+                extracted.push(item_info);
+            } else {
+                survivors.push_back(item_info);
+                #[cfg(feature = "runtime")]
+                surviving_arrivals.extend(arrival);
+            }
+        }
+        #[cfg(feature = "runtime")]
+        {
+            drop(arrivals);
+            self.arrivals = surviving_arrivals;
+        }
+
+        self.items = survivors;
+        self.expected_write_id = front_tick_id;
+        for item_info in &mut self.items {
+            item_info.tick_id = self.expected_write_id;
+            self.expected_write_id += self.tick_step;
+        }
+        self.expected_read_id = self.expected_read_id.min(self.expected_write_id);
+        self.debug_assert_invariants();
+
+        extracted
+    }
+
+    /// Keeps every `keep_every`-th item starting with the front and returns the removed ones,
+    /// e.g. to send a coarse summary of a long input history.
+    ///
+    /// Like [`Queue::extract_if`], the survivors are renumbered to stay contiguous from the
+    /// original front, which intentionally changes the tick spacing of the history.
+    /// A `keep_every` of one keeps everything.
+    ///
+    /// # Panics
+    /// Panics if `keep_every` is zero.
+    pub fn downsample(&mut self, keep_every: usize) -> Vec<ItemInfo<T>> {
+        assert!(keep_every > 0, "keep_every must be greater than zero");
+        let mut index = 0;
+        self.extract_if(|_| {
+            let remove = index % keep_every != 0;
+            index += 1;
+            remove
+        })
+    }
+
+    /// Captures the items and expected write tick, to be put back later by [`Queue::restore`].
+    #[must_use]
+    pub fn snapshot(&self) -> QueueSnapshot<T> {
+        QueueSnapshot {
+            items: self.items.clone(),
+            #[cfg(feature = "runtime")]
+            arrivals: self.arrivals.clone(),
+            expected_write_id: self.expected_write_id,
+        }
+    }
+
+    /// Replaces the items and expected write tick with the ones in `snapshot`. The read position
+    /// moves to the restored front. The replaced items count as discarded.
+    pub fn restore(&mut self, snapshot: QueueSnapshot<T>) {
+        #[cfg(feature = "runtime")]
+        let was_empty = self.items.is_empty();
+        self.discard_front(self.items.len());
+        self.items = snapshot.items;
+        #[cfg(feature = "runtime")]
+        {
+            self.arrivals = snapshot.arrivals;
+        }
+        self.expected_write_id = snapshot.expected_write_id;
+        self.expected_read_id = self.front_tick_id().unwrap_or(self.expected_write_id);
+        #[cfg(feature = "runtime")]
+        self.wake_if_filled(was_empty);
+        self.record_len();
+        self.debug_assert_invariants();
+    }
+
+    /// Computes the changes needed to turn `other` into `self`.
+    ///
+    /// Since both queues are gapless, removed and added ticks are always at either end, and only
+    /// the overlapping range needs its payloads compared.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> QueueDiff<T>
+    where
+        T: PartialEq,
+    {
+        let removed = other
+            .items
+            .iter()
+            .filter(|item_info| self.offset_from_front(item_info.tick_id).is_none())
+            .map(|item_info| item_info.tick_id)
+            .collect();
+
+        let mut changed = Vec::new();
+        let mut added = Vec::new();
+        for item_info in &self.items {
+            match other.get_by_tick_id(item_info.tick_id) {
+                Some(previous) if previous.item == item_info.item => {}
+                Some(_) => changed.push(item_info.clone()),
+                None => added.push(item_info.clone()),
+            }
+        }
+
+        QueueDiff {
+            removed,
+            changed,
+            added,
+            expected_write_id: self.expected_write_id,
+        }
+    }
+
+    /// Applies a diff computed by [`Queue::diff`]. The queue must be equal to the one the diff was
+    /// computed against, otherwise the tick sequence may end up broken. Removed items count as
+    /// discarded.
+    pub fn apply_diff(&mut self, diff: &QueueDiff<T>) {
+        #[cfg(feature = "runtime")]
+        let was_empty = self.items.is_empty();
+        let is_removed = |item_info: &ItemInfo<T>| diff.removed.contains(&item_info.tick_id);
+        let removed_front = self
+            .items
+            .iter()
+            .take_while(|item_info| is_removed(item_info))
+            .count();
+        self.discard_front(removed_front);
+        let removed_back = self
+            .items
+            .iter()
+            .rev()
+            .take_while(|item_info| is_removed(item_info))
+            .count();
+        self.discard_back(removed_back);
+
+        for item_info in &diff.changed {
+            if let Some(index) = self.offset_from_front(item_info.tick_id) {
+                self.items[index].item = item_info.item.clone();
+            }
+        }
+
+        let front_tick_id = self.front_tick_id();
+        for item_info in diff.added.iter().rev() {
+            if front_tick_id.is_some_and(|front_tick_id| item_info.tick_id < front_tick_id) {
+                self.items.push_front(item_info.clone());
+                #[cfg(feature = "runtime")]
+                if !self.arrivals.is_empty() {
+                    self.arrivals.push_front(None);
+                }
+            }
+        }
+        for item_info in &diff.added {
+            if front_tick_id.is_none_or(|front_tick_id| item_info.tick_id > front_tick_id) {
+                self.items.push_back(item_info.clone());
+                #[cfg(feature = "runtime")]
+                if !self.arrivals.is_empty() {
+                    self.arrivals.push_back(None);
+                }
+            }
+        }
+
+        self.expected_write_id = diff.expected_write_id;
+        #[cfg(feature = "runtime")]
+        self.wake_if_filled(was_empty);
+        self.record_len();
+        self.expected_read_id =
+            self.front_tick_id()
+                .map_or(self.expected_write_id, |front_tick_id| {
+                    self.expected_read_id
+                        .clamp(front_tick_id, self.expected_write_id)
+                });
+        self.debug_assert_invariants();
+    }
+
+    /// Offsets every tick in the queue, including the expected read and write ticks, by `delta`,
+    /// e.g. to reconcile peers whose tick counters started at different epochs.
+    ///
+    /// # Errors
+    /// - Returns `QueueError::TickOverflow` if any tick would end up below zero or above
+    ///   [`TICK_ID_MAX`]. Nothing is modified in that case.
+    pub fn shift_ticks(&mut self, delta: i64) -> Result<(), QueueError> {
+        let shift = |tick_id: TickId| -> Result<TickId, QueueError> {
+            u32::try_from(i64::from(tick_id.value()) + delta)
+                .map(TickId::new)
+                .map_err(|_| QueueError::TickOverflow { tick_id, delta })
+        };
+
+        let lowest = self
+            .front_tick_id()
+            .map_or(self.expected_read_id, |front_tick_id| {
+                front_tick_id.min(self.expected_read_id)
+            });
+        shift(lowest)?;
+        let expected_write_id = shift(self.expected_write_id)?;
+
+        for item_info in &mut self.items {
+            item_info.tick_id = shift(item_info.tick_id)?;
+        }
+        self.expected_read_id = shift(self.expected_read_id)?;
+        self.expected_write_id = expected_write_id;
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    /// Keeps only the items from `tick_id` onwards, dropping everything older.
+    ///
+    /// This is [`Queue::discard_up_to`] named by intent. Since it only trims the front,
+    /// the tick sequence stays contiguous.
+    pub fn retain_from_tick_id(&mut self, tick_id: TickId) {
+        self.discard_up_to(tick_id);
+    }
+
+    /// Discards up to `count` items from the front of the queue.
+    ///
+    /// Saturates at the queue length and returns how many items were actually discarded.
+    pub fn discard_count(&mut self, count: usize) -> usize {
+        self.discard_front(count.min(self.items.len()))
+    }
+
+    /// Discards the oldest items until at most `max_len` remain and returns how many were dropped.
+    ///
+    /// The expected write tick is unchanged, so pushing continues where it left off.
+    pub fn cap_to_newest(&mut self, max_len: usize) -> usize {
+        self.discard_front(self.items.len().saturating_sub(max_len))
+    }
+
+    /// Keeps only the items within `range`, e.g. the rollback window, and returns how many were
+    /// dropped from both ends.
+    ///
+    /// Items dropped from the back pull the expected write tick back as in
+    /// [`Queue::truncate_to_len`]. A range that does not overlap the buffered ticks empties the
+    /// queue.
+    pub fn trim_to_range(&mut self, range: RangeInclusive<TickId>) -> usize {
+        let dropped_front = self.advance_front_to(*range.start());
+        let keep = self
+            .items
+            .partition_point(|item_info| item_info.tick_id <= *range.end());
+        dropped_front + self.truncate_to_len(keep)
+    }
+
+    /// Drops the newest items until at most `max_len` remain and returns how many were dropped.
+    ///
+    /// The expected write tick is pulled back to the oldest dropped tick, so those ticks can be
+    /// pushed again, e.g. when rolling back speculative inputs.
+    pub fn truncate_to_len(&mut self, max_len: usize) -> usize {
+        self.discard_back(self.items.len().saturating_sub(max_len))
+    }
+
+    fn discard_front(&mut self, count: usize) -> usize {
+        if let Some(last_discarded) = count.checked_sub(1).and_then(|index| self.items.get(index)) {
+            let last_tick_id = last_discarded.tick_id;
+            let discarded = self.items.drain(..count);
+            #[cfg(feature = "runtime")]
+            if !self.arrivals.is_empty() {
+                self.arrivals.drain(..count);
+            }
+            #[cfg(feature = "runtime")]
+            if let Some(on_discard) = &mut self.on_discard {
+                discarded.for_each(on_discard);
+            } else {
+                drop(discarded);
+            }
+            #[cfg(not(feature = "runtime"))]
+            drop(discarded);
+            self.metrics.discarded += count as u64;
+            self.advance_read_past(last_tick_id);
+        }
+        self.debug_assert_invariants();
+        count
+    }
+
+    fn discard_back(&mut self, count: usize) -> usize {
+        let keep = self.items.len().saturating_sub(count);
+        if let Some(first_discarded) = self.items.get(keep) {
+            self.expected_write_id = first_discarded.tick_id;
+            self.expected_read_id = self.expected_read_id.min(self.expected_write_id);
+            let discarded = self.items.drain(keep..);
+            #[cfg(feature = "runtime")]
+            if !self.arrivals.is_empty() {
+                self.arrivals.drain(keep..);
+            }
+            #[cfg(feature = "runtime")]
+            if let Some(on_discard) = &mut self.on_discard {
+                discarded.for_each(on_discard);
+            } else {
+                drop(discarded);
+            }
+            #[cfg(not(feature = "runtime"))]
+            drop(discarded);
+            self.metrics.discarded += count as u64;
+        }
+        self.debug_assert_invariants();
+        count
+    }
+
+    /// Makes every following [`Queue::pop`] and [`Queue::take`] panic if it returns a tick at or
+    /// before one already popped, to catch consumers that accidentally process a tick twice,
+    /// e.g. after restoring an older [`QueueSnapshot`].
+    ///
+    /// Only active with debug assertions; in release builds this does nothing and costs nothing.
+    pub fn enable_monotonic_check(&mut self) {
+        #[cfg(debug_assertions)]
+        if self.monotonic_check.is_none() {
+            self.monotonic_check = Some(None);
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn check_monotonic_pop(&mut self, first_tick_id: TickId, last_tick_id: TickId) {
+        #[cfg(debug_assertions)]
+        if let Some(last_popped) = &mut self.monotonic_check {
+            if let Some(previous) = *last_popped {
+                assert!(
+                    first_tick_id > previous,
+                    "popped {first_tick_id} after already popping {previous}"
+                );
+            }
+            *last_popped = Some(last_tick_id);
+        }
+    }
+
+    fn record_len(&mut self) {
+        self.metrics.peak_len = self.metrics.peak_len.max(self.items.len());
+    }
+
+    fn advance_read_past(&mut self, tick_id: TickId) {
+        self.expected_read_id = self.expected_read_id.max(tick_id + self.tick_step);
+    }
+
+    /// Pops up to a certain amount of items from the front of the queue and returns
+    /// the first `TickId` and a vector of `T`. Returns `None` if the queue
+    /// is empty.
+    ///
+    /// # Parameters
+    /// - `count`: The number of items to pop (or fewer if not enough items are available).
+    ///
+    /// # Returns
+    /// - `Some((TickId, Vec<T>))` if there are items available.
+    /// - `None` if the queue is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use tick_id::TickId;
+    /// use tick_queue::Queue;
+    /// let mut items = Queue::new(TickId::new(0));
+    /// items.push(TickId::new(0), "Step 1").unwrap();
+    /// items.push(TickId::new(1), "Step 2").unwrap();
+    ///
+    /// let result = items.take(5);  // Will return up to 5 items (in this case 2)
+    /// if let Some((tick_id, popped_items)) = result {
+    ///     assert_eq!(tick_id, TickId::new(0));
+    ///     assert_eq!(popped_items, vec!["Step 1", "Step 2"]);
+    /// }
+    /// ```
+    ///
+    /// Like [`Queue::pop`], this reads from the read position and only removes items taken from
+    /// the front.
+    #[must_use]
+    pub fn take(&mut self, count: usize) -> Option<(TickId, Vec<T>)> {
+        let first_tick_id = self.next_pop_tick_id()?;
+        let mut items_to_take = Vec::with_capacity(count.min(self.items.len()));
+        self.read_from_cursor(count, |item_info| items_to_take.push(item_info.item));
+
+        Some((first_tick_id, items_to_take))
+    }
+
+    /// Like [`Queue::take`], but pops exactly `count` items or nothing at all, e.g. to keep
+    /// batches uniform. Returns `None` without modifying the queue if fewer are available.
+    #[must_use]
+    pub fn pop_exact(&mut self, count: usize) -> Option<(TickId, Vec<T>)> {
+        if self.items.len() - self.read_index() < count {
+            return None;
+        }
+        self.take(count)
+    }
+
+    /// Like [`Queue::take`], but appends up to `count` items to `out` and returns how many were
+    /// moved, so a per-frame loop can reuse the same buffer instead of allocating.
+    pub fn drain_into(&mut self, count: usize, out: &mut Vec<ItemInfo<T>>) -> usize {
+        self.read_from_cursor(count, |item_info| out.push(item_info))
+    }
+
+    /// Hands up to `count` items from the read position to `f` and moves the read position past
+    /// them. Items at the front are removed and count as popped; items past a moved read
+    /// position are cloned and stay buffered.
+    fn read_from_cursor<F: FnMut(ItemInfo<T>)>(&mut self, count: usize, mut f: F) -> usize {
+        let start = self.read_index();
+        let end = start.saturating_add(count).min(self.items.len());
+        if start == end {
+            return 0;
+        }
+        let last_tick_id = self.items[end - 1].tick_id;
+        self.check_monotonic_pop(self.items[start].tick_id, last_tick_id);
+
+        if start == 0 {
+            self.items.drain(..end).for_each(&mut f);
+            #[cfg(feature = "runtime")]
+            if !self.arrivals.is_empty() {
+                self.arrivals.drain(..end);
+            }
+            self.metrics.popped += end as u64;
+        } else {
+            self.items.range(start..end).cloned().for_each(&mut f);
+        }
+        self.advance_read_past(last_tick_id);
+        self.debug_assert_invariants();
+
+        end - start
+    }
+
+    /// Clones the items with a tick within `range` into a new queue, e.g. to send a tick window
+    /// to a peer, leaving this queue untouched.
+    ///
+    /// The new queue starts at the first included tick, or at `*range.start()` if no buffered
+    /// tick falls within the range.
+    #[must_use]
+    pub fn clone_range(&self, range: RangeInclusive<TickId>) -> Self {
+        let start = self
+            .items
+            .partition_point(|item_info| item_info.tick_id < *range.start());
+        let end = self
+            .items
+            .partition_point(|item_info| item_info.tick_id <= *range.end())
+            .max(start);
+
+        let front_tick_id = self
+            .items
+            .get(start)
+            .filter(|_| start < end)
+            .map_or(*range.start(), |item_info| item_info.tick_id);
+        let mut queue = Self::with_step(front_tick_id, self.tick_step);
+        queue.items = self.items.range(start..end).cloned().collect();
+        #[cfg(feature = "runtime")]
+        {
+            if !self.arrivals.is_empty() {
+                queue.arrivals = self.arrivals.range(start..end).copied().collect();
+            }
+        }
+        if let Some(back) = queue.items.back() {
+            queue.expected_write_id = back.tick_id + self.tick_step;
+        }
+        queue.record_len();
+        queue.debug_assert_invariants();
+        queue
+    }
+
+    /// Moves the oldest `count` items (or all of them, if fewer) into a new queue.
+    ///
+    /// The new queue starts at the original front and expects its next write just past the last
+    /// moved item. This queue continues from the first remaining item and keeps its expected
+    /// write tick. With `count` zero the new queue is empty and seeded at the original front.
+    #[must_use]
+    pub fn split_off_front(&mut self, count: usize) -> Self {
+        let front_tick_id = self.front_tick_id().unwrap_or(self.expected_write_id);
+        let split_count = count.min(self.items.len());
+        let items: VecDeque<_> = self.items.drain(..split_count).collect();
+        #[cfg(feature = "runtime")]
+        let arrivals = if self.arrivals.is_empty() {
+            VecDeque::new()
+        } else {
+            self.arrivals.drain(..split_count).collect()
+        };
+        self.metrics.popped += split_count as u64;
+        let expected_write_id = items.back().map_or(front_tick_id, |item_info| {
+            item_info.tick_id + self.tick_step
+        });
+        if let Some(last) = items.back() {
+            self.advance_read_past(last.tick_id);
+        }
+        self.debug_assert_invariants();
+
+        let mut front = Self {
+            items,
+            expected_read_id: front_tick_id,
+            #[cfg(feature = "runtime")]
+            arrivals,
+            ..Self::with_step(expected_write_id, self.tick_step)
+        };
+        front.record_len();
+        front.debug_assert_invariants();
+        front
+    }
+
+    /// Like [`Queue::take`], but never drains the queue below `min_remaining` items, e.g. to keep
+    /// a jitter buffer from starving during bursts.
+    ///
+    /// Only items at or after the read position count. Returns `None` without consuming anything
+    /// if there are `min_remaining` such items or fewer.
+    #[must_use]
+    pub fn take_keeping_minimum(
+        &mut self,
+        count: usize,
+        min_remaining: usize,
+    ) -> Option<(TickId, Vec<T>)> {
+        let available = (self.items.len() - self.read_index()).checked_sub(min_remaining)?;
+        if available == 0 {
+            return None;
+        }
+        self.take(count.min(available))
+    }
+
+    /// Returns the payload of the front item, without its tick.
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.items.front().map(|item_info| &item_info.item)
+    }
+
+    /// Returns the payload of the back item, without its tick.
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.items.back().map(|item_info| &item_info.item)
+    }
+
+    /// Returns the front and back items together, or `None` if the queue is empty. A single item
+    /// is returned as both ends.
+    #[must_use]
+    pub fn ends(&self) -> Option<(&ItemInfo<T>, &ItemInfo<T>)> {
+        Some((self.items.front()?, self.items.back()?))
+    }
+
+    /// Returns the most recently pushed item, e.g. to compare an incoming input against it and
+    /// drop duplicates before pushing.
+    #[must_use]
+    pub fn last_pushed(&self) -> Option<&ItemInfo<T>> {
+        self.items.back()
+    }
+
+    /// Returns a mutable reference to the front item.
+    ///
+    /// Only the payload should be modified; changing `tick_id` breaks the contiguous tick sequence.
+    #[must_use]
+    pub fn front_mut(&mut self) -> Option<&mut ItemInfo<T>> {
+        self.items.front_mut()
+    }
+
+    /// Returns a mutable reference to the back item, e.g. to patch the most recent predicted input.
+    ///
+    /// Only the payload should be modified; changing `tick_id` breaks the contiguous tick sequence.
+    #[must_use]
+    pub fn back_mut(&mut self) -> Option<&mut ItemInfo<T>> {
+        self.items.back_mut()
+    }
+
+    #[must_use]
+    pub fn front_tick_id(&self) -> Option<TickId> {
+        self.items.front().map(|item_info| item_info.tick_id)
+    }
+
+    /// Returns the tick of the item the next [`Queue::pop`] will return.
+    ///
+    /// This is the read-side counterpart of [`Queue::expected_write_tick_id`], the next tick
+    /// to go in.
+    #[must_use]
+    pub fn next_pop_tick_id(&self) -> Option<TickId> {
+        self.peek_at_read_offset(0)
+            .map(|item_info| item_info.tick_id)
+    }
+
+    /// Returns `true` if the next item to pop is exactly the tick about to be simulated.
+    #[must_use]
+    pub fn is_ready_for(&self, tick_id: TickId) -> bool {
+        self.next_pop_tick_id() == Some(tick_id)
+    }
+
+    /// Returns `true` if `count` consecutive ticks starting at `start_tick_id` are all buffered,
+    /// e.g. before committing to simulate a batch of that many ticks.
+    #[must_use]
+    pub fn has_consecutive_from(&self, start_tick_id: TickId, count: usize) -> bool {
+        let Some(last_offset) = count.checked_sub(1) else {
+            return true;
+        };
+        let Some(start_index) = self.offset_from_front(start_tick_id) else {
+            return false;
+        };
+        let expected_last = u32::try_from(last_offset)
+            .ok()
+            .and_then(|offset| offset.checked_mul(self.tick_step))
+            .and_then(|delta| start_tick_id.value().checked_add(delta));
+        start_index
+            .checked_add(last_offset)
+            .and_then(|index| self.items.get(index))
+            .is_some_and(|item_info| Some(item_info.tick_id.value()) == expected_last)
+    }
+
+    #[must_use]
+    pub const fn expected_write_tick_id(&self) -> TickId {
+        self.expected_write_id
+    }
+
+    /// Returns the item `offset` positions after the read position, i.e. the input that will be
+    /// consumed `offset` pops from now. An offset of zero is the next item to be read.
+    #[must_use]
+    pub fn peek_at_read_offset(&self, offset: usize) -> Option<&ItemInfo<T>> {
+        self.items.get(self.read_index().checked_add(offset)?)
+    }
+
+    /// Index of the first item at or after the expected read tick.
+    fn read_index(&self) -> usize {
+        self.items
+            .partition_point(|item_info| item_info.tick_id < self.expected_read_id)
+    }
+
+    /// Moves the read position to `tick_id`, so that the next [`Queue::pop`] or
+    /// [`Queue::peek_at_read_offset`] starts there, e.g. to replay from an earlier tick after a
+    /// rollback. No items are removed; items before the read position stay buffered until they
+    /// are discarded, and the front-side accessors such as [`Queue::len`] and
+    /// [`Queue::front_tick_id`] keep describing the storage.
+    ///
+    /// # Errors
+    /// Returns [`QueueError::TickNotPresent`] unless `tick_id` is a buffered tick or the
+    /// expected write tick.
+    pub fn seek_read_to(&mut self, tick_id: TickId) -> Result<(), QueueError> {
+        if tick_id != self.expected_write_id && self.index_of_tick_id(tick_id).is_none() {
+            return Err(QueueError::TickNotPresent { tick_id });
+        }
+        self.expected_read_id = tick_id;
+        Ok(())
+    }
+
+    /// Moves the read position `n` items forward, wrapping around to the front after the back,
+    /// e.g. for round-robin scheduling experiments.
+    ///
+    /// Only the read position moves; the storage is not reordered and no items are removed. As
+    /// with [`Queue::seek_read_to`], a [`Queue::pop`] past the front hands out a clone.
+    /// Rotating by a multiple of `len()` is a no-op, also when the read position is at the
+    /// expected write tick; from there a single step wraps to the front.
+    pub fn rotate_read_cursor(&mut self, n: usize) {
+        let len = self.items.len();
+        let steps = if len == 0 { 0 } else { n % len };
+        if steps == 0 {
+            return;
+        }
+        let read_index = self.read_index();
+        let index = if read_index == len {
+            steps - 1
+        } else {
+            (read_index + steps) % len
+        };
+        self.expected_read_id = self.items[index].tick_id;
+    }
+
+    /// Returns the next `TickId` to be read, i.e. the tick just past the last popped, taken
+    /// or discarded item. Stays put when the queue is drained, so skipped ticks can be detected.
+    #[must_use]
+    pub const fn expected_read_tick_id(&self) -> TickId {
+        self.expected_read_id
+    }
+
+    /// Returns the tick of the item `offset` positions after the front, whether or not it has been
+    /// buffered yet.
+    ///
+    /// Unlike index based lookups this is not bounds-checked against `len()`, which makes it
+    /// usable for scheduling ticks that have not arrived: offsets past the back continue in whole
+    /// steps from the back item. Returns `None` if the queue is empty or the tick would overflow.
+    #[must_use]
+    pub fn projected_tick_id(&self, offset: usize) -> Option<TickId> {
+        if let Some(item_info) = self.items.get(offset) {
+            return Some(item_info.tick_id);
+        }
+        let back_tick_id = self.back_tick_id()?;
+        let steps_past_back = u32::try_from(offset - (self.items.len() - 1)).ok()?;
+        let projected = back_tick_id
+            .value()
+            .checked_add(steps_past_back.checked_mul(self.tick_step)?)?;
+        Some(TickId::new(projected))
+    }
+
+    /// Returns `true` if a [`Queue::push`] at `tick_id` would be accepted: the tick is the expected
+    /// write tick, a maximum length is not reached and the tick space is not exhausted.
+    ///
+    /// Lets callers pre-check an incoming tick without constructing a [`QueueError`].
+    #[must_use]
+    pub fn accepts_tick_id(&self, tick_id: TickId) -> bool {
+        self.check_write_tick_id(tick_id).is_ok() && self.check_room(1).is_ok()
+    }
+
+    /// Returns how many items are missing between the expected write tick and an incoming
+    /// `tick_id`, i.e. how many intermediate ticks to request a resend for.
+    ///
+    /// Returns zero if `tick_id` is the expected write tick or older. For an empty queue the
+    /// expected write tick is the seed tick.
+    #[must_use]
+    pub fn missing_before(&self, tick_id: TickId) -> u32 {
+        tick_id
+            .value()
+            .saturating_sub(self.expected_write_id.value())
+            / self.tick_step
+    }
+
+    /// Returns how many more items can be pushed before the expected write tick would pass
+    /// [`TICK_ID_MAX`], so long-running services can plan a tick-space reset in time.
+    #[must_use]
+    pub fn ticks_until_overflow(&self) -> u32 {
+        (TICK_ID_MAX - self.expected_write_id.value()) / self.tick_step
+    }
+
+    /// Returns how many steps the write side is ahead of the read side.
+    ///
+    /// Pops, takes and discards move the front but never the expected write tick, so pushes always
+    /// continue where writing left off. For a non-empty queue this is `len()`, the buffered items
+    /// from the front up to the expected write tick, even if [`Queue::push_skipping`] left gaps.
+    /// For an empty queue it is the distance from the expected read tick, i.e. read ticks not yet
+    /// written again.
+    #[must_use]
+    pub fn write_read_gap(&self) -> u32 {
+        if !self.items.is_empty() {
+            return u32::try_from(self.items.len()).unwrap_or(u32::MAX);
+        }
+        self.expected_write_id
+            .value()
+            .saturating_sub(self.expected_read_id.value())
+            / self.tick_step
+    }
+
+    /// Yields every tick from `since` up to (not including) the front that the queue no longer
+    /// has, either discarded or never received. On an empty queue it runs up to the expected
+    /// write tick instead. Yields nothing if `since` is at or after that point.
+    pub fn missing_ticks_since(&self, since: TickId) -> impl Iterator<Item = TickId> {
+        let end = self.front_tick_id().unwrap_or(self.expected_write_id);
+        (since.value()..end.value())
+            .step_by(self.tick_step as usize)
+            .map(TickId::new)
+    }
+
+    /// Returns the first tick up to and including `target` that has not been received yet, or
+    /// `None` if everything up to `target` is already buffered.
+    ///
+    /// Since the queue has no internal gaps, this is the expected write tick. For an empty queue
+    /// it is the expected read tick.
+    #[must_use]
+    pub fn first_missing_up_to(&self, target: TickId) -> Option<TickId> {
+        let first_missing = if self.items.is_empty() {
+            self.expected_read_id
+        } else {
+            self.expected_write_id
+        };
+        (first_missing <= target).then_some(first_missing)
+    }
+
+    /// Alias for [`Queue::accepts_tick_id`].
+    #[must_use]
+    pub fn next_expected_matches(&self, tick_id: TickId) -> bool {
+        self.accepts_tick_id(tick_id)
+    }
+
+    #[must_use]
+    pub fn back_tick_id(&self) -> Option<TickId> {
+        self.items.back().map(|item_info| item_info.tick_id)
+    }
+
+    /// Returns how far the back item is ahead of `reference`, or `None` if the queue is empty.
+    ///
+    /// A positive value means there is buffered data ahead of the reference tick, a negative value
+    /// means the whole buffer is behind it.
+    #[must_use]
+    pub fn ticks_ahead_of(&self, reference: TickId) -> Option<i64> {
+        self.back_tick_id()
+            .map(|back_tick_id| back_tick_id - reference)
+    }
+
+    /// Returns how far the buffered length is from `target_depth`.
+    ///
+    /// Negative values mean an underrun (the consumer is starving), positive values mean an
+    /// overrun (latency is building up).
+    #[must_use]
+    pub fn buffer_pressure(&self, target_depth: usize) -> i64 {
+        self.items.len() as i64 - target_depth as i64
+    }
+
+    /// Returns a stable, order-sensitive checksum over the ticks and payloads of all items,
+    /// e.g. for peers in deterministic lockstep to detect that their inputs have diverged.
+    ///
+    /// Uses a fixed hasher instead of the randomly seeded default, so the value is the same
+    /// across runs and machines as long as `T` hashes the same way.
+    #[must_use]
+    pub fn checksum(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = Fnv1aHasher::default();
+        for item_info in &self.items {
+            item_info.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
     }
 
     #[must_use]
@@ -269,6 +2510,24 @@ impl<T: Clone> Queue<T> {
         self.items.is_empty()
     }
 
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns the approximate number of bytes allocated for the backing store.
+    ///
+    /// Counts the `ItemInfo<T>` slots and, once [`Queue::push_at`] was used, the arrival
+    /// instants kept beside them. Any heap memory owned by the payloads (e.g. a `Vec` or `String`
+    /// inside `T`) is not included.
+    #[must_use]
+    pub fn heap_size(&self) -> usize {
+        let size = self.items.capacity() * size_of::<ItemInfo<T>>();
+        #[cfg(feature = "runtime")]
+        let size = size + self.arrivals.capacity() * size_of::<Option<Instant>>();
+        size
+    }
+
     #[must_use]
     pub fn to_vec(&self) -> Vec<T> {
         let (front_slice, back_slice) = self.items.as_slices();
@@ -279,8 +2538,90 @@ impl<T: Clone> Queue<T> {
             .collect()
     }
 
+    /// Returns each item's index paired with its tick, a cheap diagnostic of the queue's shape
+    /// that does not clone or format any payloads.
+    #[must_use]
+    pub fn tick_layout(&self) -> Vec<(usize, TickId)> {
+        self.items
+            .iter()
+            .map(|item_info| item_info.tick_id)
+            .enumerate()
+            .collect()
+    }
+
+    /// Like [`Queue::to_vec`], but keeps the tick of each item.
+    #[must_use]
+    pub fn to_vec_with_ticks(&self) -> Vec<ItemInfo<T>> {
+        self.items.iter().cloned().collect()
+    }
+
+    /// Collapses runs of equal consecutive payloads into `(start tick, run length, payload)`,
+    /// e.g. to send held-down inputs compactly. See [`Queue::from_run_length`] for the inverse.
+    #[must_use]
+    pub fn run_length_encode(&self) -> Vec<(TickId, usize, T)>
+    where
+        T: PartialEq,
+    {
+        let mut runs: Vec<(TickId, usize, T)> = Vec::new();
+        for item_info in &self.items {
+            match runs.last_mut() {
+                Some((_, length, item)) if *item == item_info.item => *length += 1,
+                _ => runs.push((item_info.tick_id, 1, item_info.item.clone())),
+            }
+        }
+        runs
+    }
+
+    /// Copies the items into ordered `(tick, payload)` pairs, see [`Queue::from_pairs`].
+    #[must_use]
+    pub fn to_pairs(&self) -> Vec<(TickId, T)> {
+        self.items
+            .iter()
+            .map(|item_info| (item_info.tick_id, item_info.item.clone()))
+            .collect()
+    }
+
+    /// Copies the items into a map keyed by tick, for lookup-heavy rather than FIFO access.
+    #[must_use]
+    pub fn to_btree_map(&self) -> BTreeMap<TickId, T> {
+        self.items
+            .iter()
+            .map(|item_info| (item_info.tick_id, item_info.item.clone()))
+            .collect()
+    }
+
+    /// Iterates the queue in slices of at most `size` items, e.g. one slice per network packet.
+    /// The final slice is shorter if `len()` is not a multiple of `size`.
+    ///
+    /// Takes `&mut self` since the ring buffer is first rearranged into a single contiguous
+    /// slice. This does not change the order or ticks of the items.
+    ///
+    /// # Panics
+    /// Panics if `size` is zero, like [`slice::chunks`].
+    pub fn chunks(&mut self, size: usize) -> impl Iterator<Item = &[ItemInfo<T>]> {
+        let contiguous: &[ItemInfo<T>] = self.items.make_contiguous();
+        contiguous.chunks(size)
+    }
+
     #[must_use]
-    pub const fn iter_index(&self, start_index: usize) -> FromIndexIterator<T> {
+    pub const fn iter_index(&self, start_index: usize) -> FromIndexIterator<'_, T> {
         FromIndexIterator::new(&self.items, start_index)
     }
 }
+
+/// Iterates the items of two queues together in ascending tick order, e.g. to visualize local
+/// and remote inputs side by side. On equal ticks the item from `a` comes first.
+///
+/// Neither queue is modified.
+pub fn merge_iter<'a, T>(
+    a: &'a Queue<T>,
+    b: &'a Queue<T>,
+) -> impl Iterator<Item = &'a ItemInfo<T>> {
+    let mut a = a.items.iter().peekable();
+    let mut b = b.items.iter().peekable();
+    std::iter::from_fn(move || match (a.peek(), b.peek()) {
+        (Some(from_a), Some(from_b)) if from_b.tick_id < from_a.tick_id => b.next(),
+        (Some(_), _) => a.next(),
+        (None, _) => b.next(),
+    })
+}
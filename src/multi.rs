@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/tick-queue
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use crate::{ItemInfo, QueueError};
+use std::collections::BTreeMap;
+use tick_id::TickId;
+
+/// A tick-ordered queue that can hold several items per tick, e.g. the inputs of several
+/// players, each in its own slot.
+///
+/// Items are keyed by `(TickId, slot)` and iterate in tick order, then slot order. Unlike
+/// [`crate::Queue`] ticks do not have to be contiguous or pushed in order.
+#[derive(Debug, Clone)]
+pub struct MultiQueue<T> {
+    items: BTreeMap<(u32, usize), ItemInfo<T>>,
+}
+
+impl<T> Default for MultiQueue<T> {
+    fn default() -> Self {
+        Self {
+            items: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> MultiQueue<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// Stores `item` at `tick_id` in the given `slot`.
+    ///
+    /// # Errors
+    /// Returns [`QueueError::SlotTaken`] if the slot already holds an item for that tick.
+    pub fn push(&mut self, tick_id: TickId, slot: usize, item: T) -> Result<(), QueueError> {
+        let key = (tick_id.value(), slot);
+        if self.items.contains_key(&key) {
+            return Err(QueueError::SlotTaken { tick_id, slot });
+        }
+        self.items.insert(key, ItemInfo::new(tick_id, item));
+        Ok(())
+    }
+
+    /// Returns the items stored at `tick_id`, in slot order.
+    pub fn items_at(&self, tick_id: TickId) -> impl Iterator<Item = &T> {
+        let tick = tick_id.value();
+        self.items
+            .range((tick, usize::MIN)..=(tick, usize::MAX))
+            .map(|(_, item_info)| &item_info.item)
+    }
+
+    /// Removes every item older than `tick_id`.
+    pub fn discard_up_to(&mut self, tick_id: TickId) {
+        self.items = self.items.split_off(&(tick_id.value(), usize::MIN));
+    }
+
+    #[must_use]
+    pub fn front_tick_id(&self) -> Option<TickId> {
+        self.items
+            .first_key_value()
+            .map(|(_, item_info)| item_info.tick_id)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/tick-queue
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use crate::{ItemInfo, Queue, QueueError};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use tick_id::TickId;
+
+/// A [`Stream`] that pops items from a shared [`Queue`] in tick order.
+///
+/// The queue is owned through an `Arc<Mutex<Queue<T>>>` shared with any number of
+/// [`QueueHandle`]s. When the queue is empty the stream registers the task's waker with the
+/// queue and returns `Poll::Pending`; the next push through a handle wakes it up again.
+/// The stream never terminates on its own.
+#[derive(Debug)]
+pub struct QueueStream<T> {
+    shared: Arc<Mutex<Queue<T>>>,
+}
+
+/// A cloneable producer side of a [`QueueStream`].
+#[derive(Debug)]
+pub struct QueueHandle<T> {
+    shared: Arc<Mutex<Queue<T>>>,
+}
+
+impl<T> Clone for QueueHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+fn lock<T>(shared: &Mutex<Queue<T>>) -> MutexGuard<'_, Queue<T>> {
+    shared
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+impl<T: Clone> Queue<T> {
+    /// Moves the queue behind a shared handle and returns a [`Stream`] over its items.
+    #[must_use]
+    pub fn into_stream(self) -> QueueStream<T> {
+        QueueStream {
+            shared: Arc::new(Mutex::new(self)),
+        }
+    }
+}
+
+impl<T> QueueStream<T> {
+    /// Returns a handle for pushing items into the queue that backs this stream.
+    #[must_use]
+    pub fn handle(&self) -> QueueHandle<T> {
+        QueueHandle {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: Clone> QueueHandle<T> {
+    /// Pushes an item into the shared queue, waking the stream if it was waiting.
+    ///
+    /// # Errors
+    /// Same as [`Queue::push`].
+    pub fn push(&self, tick_id: TickId, item: T) -> Result<TickId, QueueError> {
+        lock(&self.shared).push(tick_id, item)
+    }
+
+    #[must_use]
+    pub fn expected_write_tick_id(&self) -> TickId {
+        lock(&self.shared).expected_write_tick_id()
+    }
+}
+
+impl<T: Clone> Stream for QueueStream<T> {
+    type Item = ItemInfo<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut queue = lock(&self.shared);
+        if let Some(item_info) = queue.pop() {
+            return Poll::Ready(Some(item_info));
+        }
+
+        queue.register_waker(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/tick-queue
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+use tick_id::TickId;
+use tick_queue::{MultiQueue, QueueError};
+
+#[test_log::test]
+fn two_items_at_same_tick() {
+    let mut items = MultiQueue::new();
+    items.push(TickId::new(10), 1, "player two jumps").unwrap();
+    items.push(TickId::new(10), 0, "player one moves").unwrap();
+    items.push(TickId::new(11), 0, "player one jumps").unwrap();
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+        items.items_at(TickId::new(10)).collect::<Vec<_>>(),
+        vec![&"player one moves", &"player two jumps"]
+    );
+    assert_eq!(items.items_at(TickId::new(12)).count(), 0);
+}
+
+#[test_log::test]
+fn taken_slot_is_rejected() {
+    let mut items = MultiQueue::new();
+    items.push(TickId::new(10), 0, "first").unwrap();
+
+    assert!(matches!(
+        items.push(TickId::new(10), 0, "second"),
+        Err(QueueError::SlotTaken {
+            tick_id: TickId(10),
+            slot: 0
+        })
+    ));
+    assert_eq!(
+        items.items_at(TickId::new(10)).collect::<Vec<_>>(),
+        vec![&"first"]
+    );
+}
+
+#[test_log::test]
+fn discard_up_to_drops_older_ticks() {
+    let mut items = MultiQueue::new();
+    items.push(TickId::new(10), 0, "a").unwrap();
+    items.push(TickId::new(11), 0, "b").unwrap();
+    items.push(TickId::new(11), 1, "c").unwrap();
+
+    items.discard_up_to(TickId::new(11));
+    assert_eq!(items.front_tick_id(), Some(TickId::new(11)));
+    assert_eq!(items.len(), 2);
+}
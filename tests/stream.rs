@@ -0,0 +1,45 @@
+/*
+ * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/tick-queue
+ * Licensed under the MIT License. See LICENSE in the project root for license information.
+ */
+#![cfg(feature = "futures")]
+
+use futures::executor::block_on;
+use futures::StreamExt;
+use std::thread;
+use tick_id::TickId;
+use tick_queue::Queue;
+
+#[test_log::test]
+fn stream_yields_buffered_items() {
+    let mut items = Queue::new(TickId::new(5));
+    items.push(TickId::new(5), "Move 1").unwrap();
+    items.push(TickId::new(6), "Move 2").unwrap();
+
+    let stream = items.into_stream();
+    let yielded: Vec<_> = block_on(stream.take(2).collect());
+
+    let ticks: Vec<_> = yielded.iter().map(|info| info.tick_id).collect();
+    assert_eq!(ticks, vec![TickId::new(5), TickId::new(6)]);
+}
+
+#[test_log::test]
+fn stream_wakes_on_push_from_other_task() {
+    let stream = Queue::new(TickId::new(0)).into_stream();
+    let handle = stream.handle();
+
+    let producer = thread::spawn(move || {
+        for tick in 0..4 {
+            handle.push(TickId::new(tick), tick * 10).unwrap();
+            thread::yield_now();
+        }
+    });
+
+    let yielded: Vec<_> = block_on(stream.take(4).collect());
+    producer.join().unwrap();
+
+    let ticks: Vec<_> = yielded.iter().map(|info| info.tick_id.value()).collect();
+    let payloads: Vec<_> = yielded.iter().map(|info| info.item).collect();
+    assert_eq!(ticks, vec![0, 1, 2, 3]);
+    assert_eq!(payloads, vec![0, 10, 20, 30]);
+}
@@ -2,10 +2,19 @@
  * Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/tick-queue
  * Licensed under the MIT License. See LICENSE in the project root for license information.
  */
+use std::collections::{HashSet, VecDeque};
+#[cfg(feature = "runtime")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "runtime")]
+use std::sync::Arc;
+#[cfg(feature = "runtime")]
+use std::task::{Wake, Waker};
 use tick_id::TickId;
-use tick_queue::Queue;
+use tick_queue::{
+    merge_iter, ItemInfo, Queue, QueueBuilder, QueueError, QueueMetrics, TICK_ID_MAX,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq)] // Debug is needed for asserts in tests
+#[derive(Debug, Clone, PartialEq, Eq, Hash)] // Debug is needed for asserts in tests
 pub enum GameInput {
     #[allow(unused)]
     Jumping(bool),
@@ -152,3 +161,3383 @@ fn into_iter() {
     assert_eq!(iter.next().unwrap().item, "Move 3");
     assert!(iter.next().is_none());
 }
+
+#[test_log::test]
+fn push_with_step() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+    items.push(TickId::new(12), "Sample 2").unwrap();
+    items.push(TickId::new(16), "Sample 3").unwrap();
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.tick_step(), 4);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(16)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(20));
+}
+
+#[test_log::test]
+fn lookup_with_step() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+    items.push(TickId::new(12), "Sample 2").unwrap();
+    items.push(TickId::new(16), "Sample 3").unwrap();
+
+    assert_eq!(items.index_of_tick_id(TickId::new(12)), Some(1));
+    assert_eq!(
+        items.get_by_tick_id(TickId::new(16)).unwrap().item,
+        "Sample 3"
+    );
+    assert!(items.get_by_tick_id(TickId::new(13)).is_none());
+    assert!(items.get_by_tick_id(TickId::new(4)).is_none());
+    assert!(items.get_by_tick_id(TickId::new(20)).is_none());
+}
+
+#[test_log::test]
+fn reject_non_aligned_tick_with_step() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+
+    assert!(items.push(TickId::new(9), "Sample 2").is_err());
+    assert!(items.push(TickId::new(16), "Sample 2").is_err());
+    assert_eq!(items.len(), 1);
+}
+
+#[test_log::test]
+fn lookup_with_default_step() {
+    let mut items = Queue::new(TickId::new(23));
+    items.push(TickId::new(23), "Move 1").unwrap();
+    items.push(TickId::new(24), "Move 2").unwrap();
+
+    assert_eq!(items.index_of_tick_id(TickId::new(24)), Some(1));
+    assert_eq!(
+        items.get_by_tick_id(TickId::new(23)).unwrap().item,
+        "Move 1"
+    );
+}
+
+#[test_log::test]
+fn tick_offset_below_front() {
+    let mut items = Queue::new(TickId::new(23));
+    items.push(TickId::new(23), "Move 1").unwrap();
+
+    assert_eq!(items.index_of_tick_id(TickId::new(22)), None);
+    assert_eq!(items.index_of_tick_id(TickId::new(0)), None);
+}
+
+#[test_log::test]
+fn tick_offset_at_front_and_in_range() {
+    let mut items = Queue::new(TickId::new(23));
+    items.push(TickId::new(23), "Move 1").unwrap();
+    items.push(TickId::new(24), "Move 2").unwrap();
+    items.push(TickId::new(25), "Move 3").unwrap();
+
+    assert_eq!(items.index_of_tick_id(TickId::new(23)), Some(0));
+    assert_eq!(items.index_of_tick_id(TickId::new(25)), Some(2));
+}
+
+#[test_log::test]
+fn tick_offset_beyond_back() {
+    let mut items = Queue::new(TickId::new(23));
+    items.push(TickId::new(23), "Move 1").unwrap();
+
+    assert_eq!(items.index_of_tick_id(TickId::new(24)), None);
+    assert_eq!(items.index_of_tick_id(TickId::new(u32::MAX)), None);
+}
+
+#[test_log::test]
+fn tick_offset_empty_queue() {
+    let items = Queue::<GameInput>::new(TickId::new(23));
+
+    assert_eq!(items.index_of_tick_id(TickId::new(23)), None);
+}
+
+#[test_log::test]
+fn push_and_discard_up_to_beyond_back() {
+    let mut items = Queue::new(TickId(23));
+    items.push(TickId(23), GameInput::Jumping(true)).unwrap();
+    items
+        .push(TickId(24), GameInput::MoveHorizontal(42))
+        .unwrap();
+    items.discard_up_to(TickId::new(100));
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(25));
+}
+
+#[test_log::test]
+fn accepts_tick_id_empty_queue() {
+    let items = Queue::<GameInput>::new(TickId::new(23));
+
+    assert!(items.accepts_tick_id(TickId::new(23)));
+    assert!(!items.accepts_tick_id(TickId::new(22)));
+    assert!(!items.accepts_tick_id(TickId::new(24)));
+}
+
+#[test_log::test]
+fn accepts_tick_id_populated_queue() {
+    let mut items = Queue::new(TickId::new(23));
+    items
+        .push(TickId::new(23), GameInput::Jumping(true))
+        .unwrap();
+    items
+        .push(TickId::new(24), GameInput::Jumping(false))
+        .unwrap();
+
+    assert!(items.accepts_tick_id(TickId::new(25)));
+    assert!(items.next_expected_matches(TickId::new(25)));
+    assert!(!items.accepts_tick_id(TickId::new(24)));
+    assert!(!items.next_expected_matches(TickId::new(26)));
+}
+
+#[test_log::test]
+fn accepts_tick_id_when_full_or_out_of_ticks() {
+    let mut items = Queue::new(TickId::new(23));
+    items.set_max_len(Some(1));
+    items
+        .push(TickId::new(23), GameInput::Jumping(true))
+        .unwrap();
+    assert!(!items.accepts_tick_id(TickId::new(24)));
+
+    let exhausted = Queue::<GameInput>::new(TickId::new(TICK_ID_MAX));
+    assert!(!exhausted.accepts_tick_id(TickId::new(TICK_ID_MAX)));
+}
+
+#[test_log::test]
+fn push_slice_batch() {
+    let mut items = Queue::new(TickId::new(23));
+    items
+        .push_slice(
+            TickId::new(23),
+            &[GameInput::Jumping(true), GameInput::MoveHorizontal(2)],
+        )
+        .expect("Expected batch to be accepted");
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(24)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(25));
+}
+
+#[test_log::test]
+fn push_slice_wrong_start_tick() {
+    let mut items = Queue::new(TickId::new(23));
+    items
+        .push(TickId::new(23), GameInput::Jumping(true))
+        .unwrap();
+
+    let result = items.push_slice(
+        TickId::new(25),
+        &[GameInput::Jumping(false), GameInput::MoveHorizontal(2)],
+    );
+
+    assert!(matches!(
+        result,
+        Err(QueueError::GapDetected { expected, encountered })
+            if expected == TickId::new(24) && encountered == TickId::new(25)
+    ));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(24));
+    assert_eq!(items.to_vec(), vec![GameInput::Jumping(true)]);
+}
+
+#[test_log::test]
+fn display_queue() {
+    let mut items = Queue::new(TickId::new(23));
+    items.push(TickId::new(23), "Jump").unwrap();
+    items.push(TickId::new(24), "Move(5)").unwrap();
+
+    assert_eq!(
+        items.to_string(),
+        "Queue(expected_write=tick:00000019)[tick:00000017: Jump, tick:00000018: Move(5)]"
+    );
+}
+
+#[test_log::test]
+fn display_empty_queue() {
+    let items = Queue::<&str>::new(TickId::new(42));
+
+    assert_eq!(items.to_string(), "Queue(expected_write=tick:0000002A)[]");
+}
+
+#[test_log::test]
+fn fold_items_sum() {
+    let mut items = Queue::new(TickId::new(0));
+    items.push(TickId::new(0), 3).unwrap();
+    items.push(TickId::new(1), -1).unwrap();
+    items.push(TickId::new(2), 5).unwrap();
+
+    assert_eq!(items.fold_items(0, |sum, value| sum + value), 7);
+}
+
+#[test_log::test]
+fn fold_items_in_tick_order() {
+    let mut items = Queue::new(TickId::new(0));
+    items.push(TickId::new(0), 1).unwrap();
+    items.push(TickId::new(1), 2).unwrap();
+    items.push(TickId::new(2), 3).unwrap();
+
+    assert_eq!(items.fold_items(0, |acc, digit| acc * 10 + digit), 123);
+}
+
+#[test_log::test]
+fn first_and_last() {
+    let mut items = Queue::new(TickId::new(0));
+    assert!(items.first().is_none());
+    assert!(items.last().is_none());
+
+    items.push(TickId::new(0), "Move 1").unwrap();
+    assert_eq!(items.first(), Some(&"Move 1"));
+    assert_eq!(items.last(), Some(&"Move 1"));
+
+    items.push(TickId::new(1), "Move 2").unwrap();
+    items.push(TickId::new(2), "Move 3").unwrap();
+    assert_eq!(items.first(), Some(&"Move 1"));
+    assert_eq!(items.last(), Some(&"Move 3"));
+
+    let _ = items.pop();
+    assert_eq!(items.first(), Some(&"Move 2"));
+    assert_eq!(items.last(), Some(&"Move 3"));
+}
+
+#[test_log::test]
+fn reset_with_items() {
+    let mut items = Queue::new(TickId::new(0));
+    items.push(TickId::new(0), "Old 1").unwrap();
+    items.push(TickId::new(1), "Old 2").unwrap();
+
+    items.reset_with(TickId::new(40), ["New 1", "New 2", "New 3"]);
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(40)));
+    assert_eq!(items.back_tick_id(), Some(TickId::new(42)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(43));
+    assert_eq!(items.to_vec(), vec!["New 1", "New 2", "New 3"]);
+}
+
+fn jump_and_moves() -> Queue<GameInput> {
+    let mut items = Queue::new(TickId::new(10));
+    items
+        .push(TickId::new(10), GameInput::MoveHorizontal(1))
+        .unwrap();
+    items
+        .push(TickId::new(11), GameInput::Jumping(true))
+        .unwrap();
+    items
+        .push(TickId::new(12), GameInput::MoveHorizontal(-1))
+        .unwrap();
+    items
+}
+
+#[test_log::test]
+fn position_and_find_at_front() {
+    let items = jump_and_moves();
+
+    let is_move = |info: &ItemInfo<GameInput>| matches!(info.item, GameInput::MoveHorizontal(_));
+    assert_eq!(items.position(is_move), Some(0));
+    assert_eq!(items.find(is_move).unwrap().tick_id, TickId::new(10));
+}
+
+#[test_log::test]
+fn position_and_find_in_middle() {
+    let items = jump_and_moves();
+
+    let is_jump = |info: &ItemInfo<GameInput>| matches!(info.item, GameInput::Jumping(_));
+    assert_eq!(items.position(is_jump), Some(1));
+    assert_eq!(items.find(is_jump).unwrap().tick_id, TickId::new(11));
+}
+
+#[test_log::test]
+fn position_and_find_no_match() {
+    let items = jump_and_moves();
+
+    let is_far_move = |info: &ItemInfo<GameInput>| info.item == GameInput::MoveHorizontal(100);
+    assert_eq!(items.position(is_far_move), None);
+    assert!(items.find(is_far_move).is_none());
+}
+
+#[test_log::test]
+fn binary_search_present_tick() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.binary_search_tick_id(TickId::new(10)), Ok(0));
+    assert_eq!(items.binary_search_tick_id(TickId::new(12)), Ok(2));
+}
+
+#[test_log::test]
+fn binary_search_below_front() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.binary_search_tick_id(TickId::new(3)), Err(0));
+}
+
+#[test_log::test]
+fn binary_search_above_back() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.binary_search_tick_id(TickId::new(13)), Err(3));
+    assert_eq!(items.binary_search_tick_id(TickId::new(99)), Err(3));
+}
+
+#[test_log::test]
+fn with_capacity() {
+    let mut items = Queue::with_capacity(TickId::new(5), 16);
+    assert!(items.capacity() >= 16);
+    assert!(items.is_empty());
+
+    items
+        .push(TickId::new(5), GameInput::Jumping(true))
+        .unwrap();
+    assert_eq!(items.front_tick_id(), Some(TickId::new(5)));
+    assert!(items.len() <= items.capacity());
+}
+
+#[test_log::test]
+fn invariants_hold_across_mutations() {
+    let mut items = Queue::with_capacity(TickId::new(0), 4);
+    for tick in 0..10 {
+        items.push(TickId::new(tick), tick).unwrap();
+    }
+    let _ = items.pop();
+    items.discard_up_to(TickId::new(3));
+    items.discard_count(2);
+    let _ = items.take(2);
+    items.push(TickId::new(10), 10).unwrap();
+
+    assert_eq!(items.front_tick_id(), Some(TickId::new(7)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(11));
+    assert!(items.len() <= items.capacity());
+}
+
+#[test_log::test]
+fn push_and_get_mut() {
+    let mut items = Queue::new(TickId::new(23));
+    items
+        .push(TickId::new(23), GameInput::Jumping(true))
+        .unwrap();
+
+    let info = items
+        .push_and_get_mut(TickId::new(24), GameInput::MoveHorizontal(1))
+        .expect("Expected a move horizontal tick");
+    assert_eq!(info.tick_id, TickId::new(24));
+    info.item = GameInput::MoveHorizontal(5);
+
+    let last = items.iter().last().unwrap();
+    assert_eq!(last.item, GameInput::MoveHorizontal(5));
+}
+
+#[test_log::test]
+fn push_and_get_mut_wrong_tick() {
+    let mut items = Queue::new(TickId::new(23));
+
+    assert!(items
+        .push_and_get_mut(TickId::new(24), GameInput::Jumping(true))
+        .is_err());
+    assert!(items.is_empty());
+}
+
+#[test_log::test]
+fn back_mut_patches_payload() {
+    let mut items = jump_and_moves();
+
+    items.back_mut().unwrap().item = GameInput::MoveHorizontal(-7);
+
+    assert_eq!(items.last(), Some(&GameInput::MoveHorizontal(-7)));
+    assert_eq!(items.back_tick_id(), Some(TickId::new(12)));
+}
+
+#[test_log::test]
+fn front_mut_patches_payload() {
+    let mut items = jump_and_moves();
+
+    items.front_mut().unwrap().item = GameInput::Jumping(false);
+
+    assert_eq!(items.first(), Some(&GameInput::Jumping(false)));
+    assert!(Queue::<GameInput>::default().front_mut().is_none());
+}
+
+#[test_log::test]
+fn map_payloads() {
+    let mut items = Queue::new(TickId::new(7));
+    items.push(TickId::new(7), 1).unwrap();
+    items.push(TickId::new(8), 2).unwrap();
+
+    let mapped: Queue<String> = items.map(|value| format!("#{value}"));
+
+    assert_eq!(mapped.to_vec(), vec!["#1".to_string(), "#2".to_string()]);
+    assert_eq!(mapped.front_tick_id(), Some(TickId::new(7)));
+    assert_eq!(mapped.back_tick_id(), Some(TickId::new(8)));
+    assert_eq!(mapped.expected_write_tick_id(), TickId::new(9));
+}
+
+#[test_log::test]
+fn map_ref_payloads() {
+    let mut items = Queue::new(TickId::new(7));
+    items.push(TickId::new(7), 1).unwrap();
+    items.push(TickId::new(8), 2).unwrap();
+
+    let mapped = items.map_ref(|value| value.to_string());
+
+    assert_eq!(mapped.to_vec(), vec!["1".to_string(), "2".to_string()]);
+    assert_eq!(mapped.expected_write_tick_id(), TickId::new(9));
+    assert_eq!(items.to_vec(), vec![1, 2]);
+}
+
+/// Builds a queue holding ticks 0, 1, 2 and 5, i.e. with a gap after tick 2.
+fn gapped_queue() -> Queue<&'static str> {
+    let mut items = Queue::new(TickId::new(0));
+    for (tick, name) in ["Move 1", "Move 2", "Move 3", "Move 4"].iter().enumerate() {
+        items.push(TickId::new(tick as u32), *name).unwrap();
+    }
+    items.back_mut().unwrap().tick_id = TickId::new(5);
+    items
+}
+
+#[test_log::test]
+fn iter_contiguous_yields_all() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.iter_contiguous().count(), 3);
+}
+
+#[test_log::test]
+fn iter_contiguous_stops_at_gap() {
+    let items = gapped_queue();
+
+    let ticks: Vec<_> = items.iter_contiguous().map(|info| info.tick_id).collect();
+    assert_eq!(ticks, vec![TickId::new(0), TickId::new(1), TickId::new(2)]);
+}
+
+#[test_log::test]
+fn pop_expected_matching() {
+    let mut items = jump_and_moves();
+
+    let info = items.pop_expected(TickId::new(10)).unwrap();
+    assert_eq!(info.item, GameInput::MoveHorizontal(1));
+    assert_eq!(items.front_tick_id(), Some(TickId::new(11)));
+}
+
+#[test_log::test]
+fn pop_expected_mismatch() {
+    let mut items = jump_and_moves();
+
+    let result = items.pop_expected(TickId::new(11));
+    assert!(matches!(
+        result,
+        Err(QueueError::UnexpectedReadTick { expected, encountered })
+            if expected == TickId::new(11) && encountered == TickId::new(10)
+    ));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn pop_expected_empty() {
+    let mut items = Queue::<GameInput>::new(TickId::new(10));
+
+    assert!(matches!(
+        items.pop_expected(TickId::new(10)),
+        Err(QueueError::Empty)
+    ));
+}
+
+#[test_log::test]
+fn expected_read_tick_id_starts_at_initial_tick() {
+    let items = Queue::<GameInput>::new(TickId::new(10));
+
+    assert_eq!(items.expected_read_tick_id(), TickId::new(10));
+}
+
+#[test_log::test]
+fn expected_read_tick_id_advances_on_pop_and_take() {
+    let mut items = jump_and_moves();
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+
+    let _ = items.pop();
+    assert_eq!(items.expected_read_tick_id(), TickId::new(11));
+
+    let _ = items.take(2);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+
+    let _ = items.take(5);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(14));
+
+    assert!(items.pop().is_none());
+    assert_eq!(items.expected_read_tick_id(), TickId::new(14));
+}
+
+#[test_log::test]
+fn expected_read_tick_id_advances_on_discard() {
+    let mut items = jump_and_moves();
+
+    items.discard_up_to(TickId::new(5));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(10));
+
+    items.discard_up_to(TickId::new(11));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(11));
+
+    items.discard_count(8);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn expected_read_tick_id_reset_by_clear() {
+    let mut items = jump_and_moves();
+    let _ = items.pop();
+
+    items.clear(TickId::new(100));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(100));
+}
+
+#[test_log::test]
+fn expected_read_tick_id_with_step() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+    items.push(TickId::new(12), "Sample 2").unwrap();
+
+    let _ = items.pop();
+    assert_eq!(items.expected_read_tick_id(), TickId::new(12));
+}
+
+#[test_log::test]
+fn contains_item_present() {
+    let items = jump_and_moves();
+
+    assert!(items.contains_item(&GameInput::Jumping(true)));
+    assert!(items.contains_item(&GameInput::MoveHorizontal(-1)));
+}
+
+#[test_log::test]
+fn contains_item_absent() {
+    let items = jump_and_moves();
+
+    assert!(!items.contains_item(&GameInput::Jumping(false)));
+}
+
+#[test_log::test]
+fn contains_item_empty_queue() {
+    let items = Queue::<GameInput>::default();
+
+    assert!(!items.contains_item(&GameInput::Jumping(true)));
+}
+
+#[test_log::test]
+fn ticks_ahead_of_reference_before() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.ticks_ahead_of(TickId::new(8)), Some(4));
+}
+
+#[test_log::test]
+fn ticks_ahead_of_reference_inside() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.ticks_ahead_of(TickId::new(11)), Some(1));
+    assert_eq!(items.ticks_ahead_of(TickId::new(12)), Some(0));
+}
+
+#[test_log::test]
+fn ticks_ahead_of_reference_after() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.ticks_ahead_of(TickId::new(15)), Some(-3));
+    assert_eq!(
+        Queue::<GameInput>::default().ticks_ahead_of(TickId::new(0)),
+        None
+    );
+}
+
+#[test_log::test]
+fn retain_from_tick_id_lower() {
+    let mut items = jump_and_moves();
+    items.retain_from_tick_id(TickId::new(1));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn retain_from_tick_id_equal() {
+    let mut items = jump_and_moves();
+    items.retain_from_tick_id(TickId::new(11));
+    assert_eq!(items.len(), 2);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(11)));
+}
+
+#[test_log::test]
+fn retain_from_tick_id_beyond_back() {
+    let mut items = jump_and_moves();
+    items.retain_from_tick_id(TickId::new(20));
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn to_vec_with_ticks() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items.to_vec_with_ticks(),
+        vec![
+            ItemInfo {
+                item: GameInput::MoveHorizontal(1),
+                tick_id: TickId::new(10)
+            },
+            ItemInfo {
+                item: GameInput::Jumping(true),
+                tick_id: TickId::new(11)
+            },
+            ItemInfo {
+                item: GameInput::MoveHorizontal(-1),
+                tick_id: TickId::new(12)
+            },
+        ]
+    );
+}
+
+#[test_log::test]
+fn chunks_evenly_divisible() {
+    let mut items = Queue::new(TickId::new(0));
+    for tick in 0..6 {
+        items.push(TickId::new(tick), tick).unwrap();
+    }
+
+    let chunks: Vec<Vec<u32>> = items
+        .chunks(2)
+        .map(|chunk| chunk.iter().map(|info| info.item).collect())
+        .collect();
+    assert_eq!(chunks, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+}
+
+#[test_log::test]
+fn chunks_with_remainder() {
+    let mut items = Queue::new(TickId::new(0));
+    for tick in 0..8 {
+        items.push(TickId::new(tick), tick).unwrap();
+    }
+    let _ = items.take(3);
+    for tick in 8..10 {
+        items.push(TickId::new(tick), tick).unwrap();
+    }
+
+    let chunk_lengths: Vec<usize> = items.chunks(3).map(<[_]>::len).collect();
+    assert_eq!(chunk_lengths, vec![3, 3, 1]);
+    let first_ticks: Vec<TickId> = items.chunks(3).map(|chunk| chunk[0].tick_id).collect();
+    assert_eq!(
+        first_ticks,
+        vec![TickId::new(3), TickId::new(6), TickId::new(9)]
+    );
+}
+
+#[test_log::test]
+#[should_panic]
+fn chunks_zero_size() {
+    let mut items = jump_and_moves();
+    let _ = items.chunks(0).count();
+}
+
+#[test_log::test]
+fn from_items_round_trips_take() {
+    let mut items = jump_and_moves();
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    let _ = items.pop();
+    let original = items.to_vec_with_ticks();
+
+    let (front_tick_id, payloads) = items.take(10).unwrap();
+    let rebuilt = Queue::from_items(front_tick_id, payloads);
+
+    assert_eq!(rebuilt.to_vec_with_ticks(), original);
+    assert_eq!(rebuilt.expected_write_tick_id(), TickId::new(14));
+}
+
+#[test_log::test]
+fn from_items_empty() {
+    let rebuilt = Queue::<GameInput>::from_items(TickId::new(42), Vec::new());
+
+    assert!(rebuilt.is_empty());
+    assert_eq!(rebuilt.expected_write_tick_id(), TickId::new(42));
+}
+
+#[test_log::test]
+#[should_panic(expected = "TICK_ID_MAX")]
+fn from_items_past_tick_space() {
+    let _ = Queue::from_items(TickId::new(TICK_ID_MAX), vec![1]);
+}
+
+#[test_log::test]
+#[should_panic(expected = "TICK_ID_MAX")]
+fn from_run_length_past_tick_space() {
+    let _ = Queue::from_run_length(TickId::new(TICK_ID_MAX - 1), &[(2, 'a')]);
+}
+
+#[test_log::test]
+#[should_panic(expected = "TICK_ID_MAX")]
+fn reset_with_past_tick_space() {
+    let mut items = Queue::new(TickId::new(0));
+    items.reset_with(TickId::new(TICK_ID_MAX), [1]);
+}
+
+#[test_log::test]
+fn overwrite_range_fully_overlapping() {
+    let mut items = jump_and_moves();
+
+    items
+        .overwrite_range(
+            TickId::new(11),
+            &[GameInput::Jumping(false), GameInput::MoveHorizontal(9)],
+        )
+        .expect("Expected correction to be applied");
+
+    assert_eq!(
+        items.to_vec(),
+        vec![
+            GameInput::MoveHorizontal(1),
+            GameInput::Jumping(false),
+            GameInput::MoveHorizontal(9)
+        ]
+    );
+    assert_eq!(items.front_tick_id(), Some(TickId::new(10)));
+    assert_eq!(items.back_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn overwrite_range_partially_out_of_range() {
+    let mut items = jump_and_moves();
+    let before = items.to_vec_with_ticks();
+
+    let result = items.overwrite_range(
+        TickId::new(12),
+        &[GameInput::Jumping(false), GameInput::MoveHorizontal(9)],
+    );
+
+    assert!(matches!(
+        result,
+        Err(QueueError::TickNotPresent { tick_id }) if tick_id == TickId::new(13)
+    ));
+    assert_eq!(items.to_vec_with_ticks(), before);
+}
+
+#[test_log::test]
+fn overwrite_range_below_front() {
+    let mut items = jump_and_moves();
+    let before = items.to_vec_with_ticks();
+
+    let result = items.overwrite_range(TickId::new(9), &[GameInput::Jumping(false)]);
+
+    assert!(matches!(
+        result,
+        Err(QueueError::TickNotPresent { tick_id }) if tick_id == TickId::new(9)
+    ));
+    assert_eq!(items.to_vec_with_ticks(), before);
+}
+
+#[test_log::test]
+fn into_parts_and_from_parts_round_trip() {
+    let items = jump_and_moves();
+    let original = items.to_vec_with_ticks();
+
+    let (deque, expected_write_id) = items.into_parts();
+    assert_eq!(expected_write_id, TickId::new(13));
+    assert_eq!(deque.len(), 3);
+
+    let rebuilt = Queue::from_parts(deque, expected_write_id);
+    assert_eq!(rebuilt.to_vec_with_ticks(), original);
+    assert_eq!(rebuilt.expected_write_tick_id(), TickId::new(13));
+    assert_eq!(rebuilt.expected_read_tick_id(), TickId::new(10));
+}
+
+#[cfg(debug_assertions)]
+#[test_log::test]
+#[should_panic]
+fn from_parts_malformed_deque() {
+    let (mut deque, expected_write_id) = jump_and_moves().into_parts();
+    deque[1].tick_id = TickId::new(20);
+
+    let _ = Queue::from_parts(deque, expected_write_id);
+}
+
+#[test_log::test]
+fn discard_count_reports_less_than_len() {
+    let mut items = jump_and_moves();
+    assert_eq!(items.discard_count(2), 2);
+    assert_eq!(items.len(), 1);
+}
+
+#[test_log::test]
+fn discard_count_reports_equal_to_len() {
+    let mut items = jump_and_moves();
+    assert_eq!(items.discard_count(3), 3);
+    assert!(items.is_empty());
+}
+
+#[test_log::test]
+fn discard_count_reports_greater_than_len() {
+    let mut items = jump_and_moves();
+    assert_eq!(items.discard_count(8), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.discard_count(8), 0);
+}
+
+#[test_log::test]
+fn buffer_pressure_under_target() {
+    let items = jump_and_moves();
+    assert_eq!(items.buffer_pressure(5), -2);
+}
+
+#[test_log::test]
+fn buffer_pressure_at_target() {
+    let items = jump_and_moves();
+    assert_eq!(items.buffer_pressure(3), 0);
+}
+
+fn skipped_to_five() -> Queue<u32> {
+    let mut items = Queue::new(TickId::new(0));
+    items.push(TickId::new(0), 0).unwrap();
+    items.push(TickId::new(1), 1).unwrap();
+    assert_eq!(items.push_skipping(TickId::new(5), 5).unwrap(), 3);
+    items
+}
+
+#[test_log::test]
+fn overwrite_range_over_skipped_ticks() {
+    let mut items = skipped_to_five();
+
+    assert!(matches!(
+        items.overwrite_range(TickId::new(1), &[10, 20]),
+        Err(QueueError::TickNotPresent { tick_id: TickId(2) })
+    ));
+    assert_eq!(items.to_vec(), vec![0, 1, 5]);
+
+    items.overwrite_range(TickId::new(5), &[50]).unwrap();
+    assert_eq!(items.to_vec(), vec![0, 1, 50]);
+}
+
+#[test_log::test]
+fn buffer_pressure_over_target() {
+    let items = jump_and_moves();
+    assert_eq!(items.buffer_pressure(1), 2);
+}
+
+#[test_log::test]
+fn projected_tick_id_beyond_len() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.projected_tick_id(0), Some(TickId::new(10)));
+    assert_eq!(items.projected_tick_id(2), Some(TickId::new(12)));
+    assert_eq!(items.projected_tick_id(7), Some(TickId::new(17)));
+}
+
+#[test_log::test]
+fn projected_tick_id_empty_and_step() {
+    assert_eq!(
+        Queue::<GameInput>::new(TickId::new(3)).projected_tick_id(1),
+        None
+    );
+
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+    assert_eq!(items.projected_tick_id(3), Some(TickId::new(20)));
+}
+
+#[test_log::test]
+fn projected_tick_id_over_skipped_ticks() {
+    let items = skipped_to_five();
+
+    assert_eq!(items.projected_tick_id(1), Some(TickId::new(1)));
+    assert_eq!(items.projected_tick_id(2), Some(TickId::new(5)));
+    assert_eq!(items.projected_tick_id(3), Some(TickId::new(6)));
+}
+
+#[test_log::test]
+fn merge_overwrite_pure_overlap() {
+    let mut items = jump_and_moves();
+    let mut other = Queue::new(TickId::new(11));
+    other
+        .push(TickId::new(11), GameInput::Jumping(false))
+        .unwrap();
+
+    items
+        .merge_overwrite(&other)
+        .expect("Expected overlap to merge");
+
+    assert_eq!(
+        items.to_vec(),
+        vec![
+            GameInput::MoveHorizontal(1),
+            GameInput::Jumping(false),
+            GameInput::MoveHorizontal(-1)
+        ]
+    );
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn merge_overwrite_overlap_and_extension() {
+    let mut items = jump_and_moves();
+    let mut other = Queue::new(TickId::new(12));
+    other
+        .push(TickId::new(12), GameInput::MoveHorizontal(3))
+        .unwrap();
+    other
+        .push(TickId::new(13), GameInput::MoveHorizontal(4))
+        .unwrap();
+
+    items
+        .merge_overwrite(&other)
+        .expect("Expected merge with extension");
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(
+        items.get_by_tick_id(TickId::new(12)).unwrap().item,
+        GameInput::MoveHorizontal(3)
+    );
+    assert_eq!(items.last(), Some(&GameInput::MoveHorizontal(4)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(14));
+}
+
+#[test_log::test]
+fn merge_overwrite_gap_rejected() {
+    let mut items = jump_and_moves();
+    let before = items.to_vec_with_ticks();
+    let mut other = Queue::new(TickId::new(14));
+    other
+        .push(TickId::new(14), GameInput::MoveHorizontal(3))
+        .unwrap();
+
+    assert!(matches!(
+        items.merge_overwrite(&other),
+        Err(QueueError::GapDetected { expected, encountered })
+            if expected == TickId::new(13) && encountered == TickId::new(14)
+    ));
+    assert_eq!(items.to_vec_with_ticks(), before);
+}
+
+#[test_log::test]
+fn iter_offsets_match_ticks() {
+    let items = jump_and_moves();
+    let front_tick_id = items.front_tick_id().unwrap();
+
+    let offsets: Vec<usize> = items.iter_offsets().map(|(offset, _)| offset).collect();
+    assert_eq!(offsets, vec![0, 1, 2]);
+    for (offset, info) in items.iter_offsets() {
+        assert_eq!((info.tick_id - front_tick_id) as usize, offset);
+    }
+}
+
+#[test_log::test]
+fn peek_at_read_offset_zero_is_front() {
+    let mut items = jump_and_moves();
+    let _ = items.pop();
+
+    let info = items.peek_at_read_offset(0).unwrap();
+    assert_eq!(info.tick_id, items.front_tick_id().unwrap());
+    assert_eq!(info.tick_id, items.expected_read_tick_id());
+}
+
+#[test_log::test]
+fn peek_at_read_offset_valid() {
+    let items = jump_and_moves();
+
+    let info = items.peek_at_read_offset(2).unwrap();
+    assert_eq!(info.tick_id, TickId::new(12));
+    assert_eq!(info.item, GameInput::MoveHorizontal(-1));
+}
+
+#[test_log::test]
+fn peek_at_read_offset_out_of_range() {
+    let items = jump_and_moves();
+
+    assert!(items.peek_at_read_offset(3).is_none());
+    assert!(items.peek_at_read_offset(usize::MAX).is_none());
+}
+
+#[test_log::test]
+fn verify_valid_queue() {
+    assert!(jump_and_moves().verify().is_ok());
+    assert!(Queue::<GameInput>::default().verify().is_ok());
+}
+
+#[test_log::test]
+fn verify_corrupted_queue() {
+    let items = gapped_queue();
+
+    assert!(matches!(
+        items.verify(),
+        Err(QueueError::BrokenSequence { at_index: 3, expected, encountered })
+            if expected == TickId::new(3) && encountered == TickId::new(5)
+    ));
+}
+
+#[test_log::test]
+fn verify_wrong_expected_write_tick() {
+    let mut items = Queue::new(TickId::new(10));
+    items
+        .push(TickId::new(10), GameInput::Jumping(true))
+        .unwrap();
+    items.front_mut().unwrap().tick_id = TickId::new(5);
+
+    assert!(matches!(
+        items.verify(),
+        Err(QueueError::BrokenSequence { at_index: 1, expected, encountered })
+            if expected == TickId::new(6) && encountered == TickId::new(11)
+    ));
+}
+
+#[test_log::test]
+fn advance_front_to_below_front() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.advance_front_to(TickId::new(4)), 0);
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(10));
+}
+
+#[test_log::test]
+fn advance_front_to_inside_range() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.advance_front_to(TickId::new(12)), 2);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(12));
+}
+
+#[test_log::test]
+fn advance_front_to_above_back() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.advance_front_to(TickId::new(40)), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn hash_equal_queues() {
+    let mut set = HashSet::new();
+    set.insert(jump_and_moves());
+    set.insert(jump_and_moves());
+
+    assert_eq!(set.len(), 1);
+}
+
+#[test_log::test]
+fn hash_different_queues() {
+    let mut popped = jump_and_moves();
+    let _ = popped.pop();
+
+    let mut set = HashSet::new();
+    set.insert(jump_and_moves());
+    set.insert(popped);
+    set.insert(Queue::new(TickId::new(13)));
+
+    assert_eq!(set.len(), 3);
+    assert_ne!(jump_and_moves(), Queue::new(TickId::new(13)));
+}
+
+#[test_log::test]
+fn queues_with_different_steps_differ() {
+    let stepped = Queue::<GameInput>::with_step(TickId::new(0), 4);
+    let single = Queue::<GameInput>::new(TickId::new(0));
+
+    assert_ne!(stepped, single);
+
+    let mut set = HashSet::new();
+    set.insert(stepped);
+    set.insert(single);
+    assert_eq!(set.len(), 2);
+}
+
+#[test_log::test]
+fn extract_if_from_middle() {
+    let mut items = jump_and_moves();
+    items
+        .push(TickId::new(13), GameInput::MoveHorizontal(7))
+        .unwrap();
+
+    let extracted = items.extract_if(|info| matches!(info.item, GameInput::Jumping(_)));
+
+    assert_eq!(extracted.len(), 1);
+    assert_eq!(extracted[0].tick_id, TickId::new(11));
+    assert_eq!(extracted[0].item, GameInput::Jumping(true));
+
+    assert!(items.verify().is_ok());
+    let ticks: Vec<TickId> = items.iter().map(|info| info.tick_id).collect();
+    assert_eq!(
+        ticks,
+        vec![TickId::new(10), TickId::new(11), TickId::new(12)]
+    );
+    assert_eq!(
+        items.to_vec(),
+        vec![
+            GameInput::MoveHorizontal(1),
+            GameInput::MoveHorizontal(-1),
+            GameInput::MoveHorizontal(7)
+        ]
+    );
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+}
+
+#[test_log::test]
+fn extract_if_everything() {
+    let mut items = jump_and_moves();
+
+    let extracted = items.extract_if(|_| true);
+
+    assert_eq!(extracted.len(), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(10));
+}
+
+#[cfg(feature = "runtime")]
+#[derive(Default)]
+struct CountingWaker {
+    wake_count: AtomicUsize,
+}
+
+#[cfg(feature = "runtime")]
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn waker_woken_on_empty_to_non_empty() {
+    let counter = Arc::new(CountingWaker::default());
+    let mut items = Queue::new(TickId::new(0));
+    items.register_waker(Waker::from(counter.clone()));
+
+    items.push(TickId::new(0), "Move 1").unwrap();
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 1);
+
+    items.push(TickId::new(1), "Move 2").unwrap();
+    items.push(TickId::new(2), "Move 3").unwrap();
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 1);
+
+    let _ = items.take(3);
+    items.push(TickId::new(3), "Move 4").unwrap();
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn waker_woken_when_refilled_in_bulk() {
+    let counter = Arc::new(CountingWaker::default());
+    let mut items = Queue::new(TickId::new(0));
+    items.register_waker(Waker::from(counter.clone()));
+    let snapshot = jump_and_moves().snapshot();
+
+    items
+        .set_contents(vec![ItemInfo {
+            tick_id: TickId::new(0),
+            item: GameInput::Jumping(true),
+        }])
+        .unwrap();
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 1);
+
+    items.clear(TickId::new(0));
+    items.restore(snapshot);
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 2);
+
+    let target = items.clone_range(TickId::new(10)..=TickId::new(12));
+    items.clear(TickId::new(10));
+    let diff = target.diff(&items);
+    items.apply_diff(&diff);
+    assert_eq!(items.len(), 3);
+    assert_eq!(counter.wake_count.load(Ordering::SeqCst), 3);
+}
+
+#[test_log::test]
+fn diff_extension_only() {
+    let before = jump_and_moves();
+    let mut after = jump_and_moves();
+    after
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+
+    let diff = after.diff(&before);
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+    assert_eq!(
+        diff.added,
+        vec![ItemInfo {
+            item: GameInput::Jumping(false),
+            tick_id: TickId::new(13)
+        }]
+    );
+
+    let mut patched = jump_and_moves();
+    patched.apply_diff(&diff);
+    assert_eq!(patched, after);
+}
+
+#[test_log::test]
+fn diff_payload_change() {
+    let before = jump_and_moves();
+    let mut after = jump_and_moves();
+    after.back_mut().unwrap().item = GameInput::MoveHorizontal(8);
+
+    let diff = after.diff(&before);
+    assert!(diff.removed.is_empty());
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].tick_id, TickId::new(12));
+
+    let mut patched = jump_and_moves();
+    patched.apply_diff(&diff);
+    assert_eq!(patched, after);
+    assert!(after.diff(&after).is_empty());
+}
+
+#[test_log::test]
+fn diff_front_trim() {
+    let before = jump_and_moves();
+    let mut after = jump_and_moves();
+    after.discard_count(2);
+
+    let diff = after.diff(&before);
+    assert_eq!(diff.removed, vec![TickId::new(10), TickId::new(11)]);
+    assert!(diff.changed.is_empty());
+    assert!(diff.added.is_empty());
+
+    let mut patched = jump_and_moves();
+    patched.apply_diff(&diff);
+    assert_eq!(patched, after);
+    assert_eq!(patched.front_tick_id(), Some(TickId::new(12)));
+}
+
+#[test_log::test]
+fn pop_while_stops_midway() {
+    let mut items = jump_and_moves();
+
+    let popped = items.pop_while(|info| info.tick_id < TickId::new(12));
+
+    let ticks: Vec<TickId> = popped.iter().map(|info| info.tick_id).collect();
+    assert_eq!(ticks, vec![TickId::new(10), TickId::new(11)]);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(12));
+}
+
+#[test_log::test]
+fn pop_while_consumes_everything() {
+    let mut items = jump_and_moves();
+
+    let popped = items.pop_while(|_| true);
+
+    assert_eq!(popped.len(), 3);
+    assert!(items.is_empty());
+    assert!(items.pop_while(|_| true).is_empty());
+}
+
+#[test_log::test]
+fn convert_into_vec() {
+    let expected = jump_and_moves().to_vec_with_ticks();
+
+    let converted: Vec<ItemInfo<GameInput>> = jump_and_moves().into();
+
+    assert_eq!(converted, expected);
+}
+
+#[test_log::test]
+fn convert_into_vec_deque() {
+    let expected = jump_and_moves().to_vec_with_ticks();
+
+    let converted: VecDeque<ItemInfo<GameInput>> = jump_and_moves().into();
+
+    assert_eq!(converted.front().unwrap().tick_id, TickId::new(10));
+    assert_eq!(converted.into_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test_log::test]
+fn push_skipping_in_order() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(
+        items
+            .push_skipping(TickId::new(13), GameInput::Jumping(false))
+            .unwrap(),
+        0
+    );
+    assert!(items.verify().is_ok());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(14));
+}
+
+#[test_log::test]
+fn push_skipping_forward() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(
+        items
+            .push_skipping(TickId::new(17), GameInput::Jumping(false))
+            .unwrap(),
+        4
+    );
+    assert_eq!(items.len(), 4);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(17)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(18));
+    assert_eq!(
+        items.get_by_tick_id(TickId::new(17)).unwrap().item,
+        GameInput::Jumping(false)
+    );
+    assert_eq!(items.index_of_tick_id(TickId::new(12)), Some(2));
+    assert!(items.get_by_tick_id(TickId::new(13)).is_none());
+    assert!(items.verify().is_err());
+
+    items
+        .push(TickId::new(18), GameInput::Jumping(true))
+        .unwrap();
+    assert_eq!(items.index_of_tick_id(TickId::new(18)), Some(4));
+}
+
+#[test_log::test]
+fn push_skipping_stale_tick() {
+    let mut items = jump_and_moves();
+
+    assert!(matches!(
+        items.push_skipping(TickId::new(12), GameInput::Jumping(false)),
+        Err(QueueError::DuplicateTickId { tick_id }) if tick_id == TickId::new(12)
+    ));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn push_skipping_empty_queue() {
+    let mut items = Queue::new(TickId::new(10));
+
+    assert_eq!(items.push_skipping(TickId::new(15), "Move 1").unwrap(), 5);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(15)));
+    assert!(items.verify().is_ok());
+}
+
+#[test_log::test]
+fn missing_before_expected_tick() {
+    let items = jump_and_moves();
+    assert_eq!(items.missing_before(TickId::new(13)), 0);
+}
+
+#[test_log::test]
+fn missing_before_ahead() {
+    let items = jump_and_moves();
+    assert_eq!(items.missing_before(TickId::new(17)), 4);
+
+    let empty = Queue::<GameInput>::new(TickId::new(40));
+    assert_eq!(empty.missing_before(TickId::new(42)), 2);
+}
+
+#[test_log::test]
+fn missing_before_behind() {
+    let items = jump_and_moves();
+    assert_eq!(items.missing_before(TickId::new(11)), 0);
+    assert_eq!(items.missing_before(TickId::new(0)), 0);
+}
+
+#[test_log::test]
+fn checksum_identical_queues() {
+    assert_eq!(jump_and_moves().checksum(), jump_and_moves().checksum());
+}
+
+#[test_log::test]
+fn checksum_detects_changes() {
+    let original = jump_and_moves().checksum();
+
+    let mut changed = jump_and_moves();
+    changed.back_mut().unwrap().item = GameInput::MoveHorizontal(2);
+    assert_ne!(changed.checksum(), original);
+
+    let mut reordered = Queue::new(TickId::new(10));
+    reordered
+        .push(TickId::new(10), GameInput::Jumping(true))
+        .unwrap();
+    reordered
+        .push(TickId::new(11), GameInput::MoveHorizontal(1))
+        .unwrap();
+    reordered
+        .push(TickId::new(12), GameInput::MoveHorizontal(-1))
+        .unwrap();
+    assert_ne!(reordered.checksum(), original);
+}
+
+#[test_log::test]
+fn checksum_is_stable() {
+    let mut items = Queue::new(TickId::new(1));
+    items.push(TickId::new(1), 7_u32).unwrap();
+
+    assert_eq!(items.checksum(), 0xebd2_4f1f_5d82_17f3);
+}
+
+#[test_log::test]
+fn iter_filter_keeps_ticks() {
+    let mut items = jump_and_moves();
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+
+    let jump_ticks: Vec<TickId> = items
+        .iter_filter(|input| matches!(input, GameInput::Jumping(_)))
+        .map(|info| info.tick_id)
+        .collect();
+
+    assert_eq!(jump_ticks, vec![TickId::new(11), TickId::new(13)]);
+}
+
+#[cfg(feature = "testing")]
+#[test_log::test]
+fn verify_reports_gap_in_raw_queue() {
+    let raw: VecDeque<ItemInfo<&str>> = [(20, "Move 1"), (21, "Move 2"), (23, "Move 3")]
+        .into_iter()
+        .map(|(tick, item)| ItemInfo {
+            item,
+            tick_id: TickId::new(tick),
+        })
+        .collect();
+    let items = Queue::from_raw_unchecked(raw, TickId::new(24));
+
+    assert!(matches!(
+        items.verify(),
+        Err(QueueError::BrokenSequence { at_index: 2, expected, encountered })
+            if expected == TickId::new(22) && encountered == TickId::new(23)
+    ));
+    assert_eq!(items.iter_contiguous().count(), 2);
+}
+
+#[cfg(feature = "testing")]
+#[test_log::test]
+fn verify_reports_wrong_expected_write_in_raw_queue() {
+    let raw: VecDeque<ItemInfo<&str>> = [ItemInfo {
+        item: "Move 1",
+        tick_id: TickId::new(20),
+    }]
+    .into_iter()
+    .collect();
+    let items = Queue::from_raw_unchecked(raw, TickId::new(30));
+
+    assert!(matches!(
+        items.verify(),
+        Err(QueueError::BrokenSequence { at_index: 1, expected, encountered })
+            if expected == TickId::new(21) && encountered == TickId::new(30)
+    ));
+}
+
+#[cfg(feature = "testing")]
+#[test_log::test]
+fn verify_reports_overflow_in_raw_queue() {
+    let raw: VecDeque<ItemInfo<&str>> = ["Move 1", "Move 2"]
+        .into_iter()
+        .map(|item| ItemInfo {
+            item,
+            tick_id: TickId::new(TICK_ID_MAX),
+        })
+        .collect();
+    let items = Queue::from_raw_unchecked(raw, TickId::new(TICK_ID_MAX));
+
+    assert!(matches!(
+        items.verify(),
+        Err(QueueError::TickOverflow { tick_id, delta: 1 }) if tick_id == TickId::new(TICK_ID_MAX)
+    ));
+}
+
+#[test_log::test]
+fn heap_size_covers_items() {
+    let mut items = Queue::new(TickId::new(0));
+    for tick in 0..5 {
+        items.push(TickId::new(tick), u64::from(tick)).unwrap();
+    }
+
+    assert!(items.heap_size() >= items.len() * size_of::<ItemInfo<u64>>());
+    assert_eq!(Queue::<u64>::new(TickId::new(0)).heap_size(), 0);
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn heap_size_covers_arrival_instants() {
+    let mut items = Queue::with_capacity(TickId::new(0), 4);
+    items.push(TickId::new(0), 0_u64).unwrap();
+    let without_arrivals = items.heap_size();
+    assert_eq!(
+        without_arrivals,
+        items.capacity() * size_of::<ItemInfo<u64>>()
+    );
+
+    items
+        .push_at(TickId::new(1), 1, std::time::Instant::now())
+        .unwrap();
+    assert!(
+        items.heap_size()
+            >= without_arrivals + items.len() * size_of::<Option<std::time::Instant>>()
+    );
+}
+
+#[test_log::test]
+fn cap_to_newest_already_shorter() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.cap_to_newest(5), 0);
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn cap_to_newest_drops_excess() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.cap_to_newest(1), 2);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+}
+
+#[test_log::test]
+fn truncate_to_len_reclaims_ticks() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.truncate_to_len(1), 2);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(10)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(11));
+
+    items
+        .push(TickId::new(11), GameInput::Jumping(false))
+        .unwrap();
+    items
+        .push(TickId::new(12), GameInput::MoveHorizontal(3))
+        .unwrap();
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn truncate_to_len_already_short() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.truncate_to_len(3), 0);
+    assert_eq!(items.truncate_to_len(10), 0);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn truncate_to_len_zero() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.truncate_to_len(0), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(10));
+    items
+        .push(TickId::new(10), GameInput::Jumping(false))
+        .unwrap();
+}
+
+#[test_log::test]
+fn is_ready_for_matching_front() {
+    let items = jump_and_moves();
+    assert!(items.is_ready_for(TickId::new(10)));
+}
+
+#[test_log::test]
+fn is_ready_for_non_matching_front() {
+    let items = jump_and_moves();
+    assert!(!items.is_ready_for(TickId::new(11)));
+    assert!(!items.is_ready_for(TickId::new(9)));
+}
+
+#[test_log::test]
+fn is_ready_for_empty_queue() {
+    let items = Queue::<GameInput>::new(TickId::new(10));
+    assert!(!items.is_ready_for(TickId::new(10)));
+}
+
+#[test_log::test]
+fn take_keeping_minimum_limited_by_minimum() {
+    let mut items = jump_and_moves();
+
+    let (tick_id, taken) = items.take_keeping_minimum(3, 2).unwrap();
+    assert_eq!(tick_id, TickId::new(10));
+    assert_eq!(taken, vec![GameInput::MoveHorizontal(1)]);
+    assert_eq!(items.len(), 2);
+}
+
+#[test_log::test]
+fn take_keeping_minimum_blocks_consumption() {
+    let mut items = jump_and_moves();
+
+    assert!(items.take_keeping_minimum(2, 3).is_none());
+    assert!(items.take_keeping_minimum(2, 5).is_none());
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn take_keeping_minimum_limited_by_count() {
+    let mut items = jump_and_moves();
+
+    let (tick_id, taken) = items.take_keeping_minimum(1, 0).unwrap();
+    assert_eq!(tick_id, TickId::new(10));
+    assert_eq!(taken.len(), 1);
+    assert_eq!(items.len(), 2);
+}
+
+#[test_log::test]
+fn extend_from_queue_clones() {
+    let mut items = jump_and_moves();
+    let mut other = Queue::new(TickId::new(13));
+    other
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    other
+        .push(TickId::new(14), GameInput::MoveHorizontal(2))
+        .unwrap();
+    let other_before = other.to_vec_with_ticks();
+
+    items
+        .extend_from_queue(&other)
+        .expect("Expected other to line up");
+
+    assert_eq!(items.len(), 5);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(14)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(15));
+    assert_eq!(other.to_vec_with_ticks(), other_before);
+}
+
+#[test_log::test]
+fn extend_from_queue_gap_rejected() {
+    let mut items = jump_and_moves();
+    let mut other = Queue::new(TickId::new(14));
+    other
+        .push(TickId::new(14), GameInput::Jumping(false))
+        .unwrap();
+    let other_before = other.to_vec_with_ticks();
+
+    assert!(matches!(
+        items.extend_from_queue(&other),
+        Err(QueueError::GapDetected { expected, encountered })
+            if expected == TickId::new(13) && encountered == TickId::new(14)
+    ));
+    assert_eq!(items.len(), 3);
+    assert_eq!(other.to_vec_with_ticks(), other_before);
+}
+
+#[test_log::test]
+fn tick_ids_in_order() {
+    let items = jump_and_moves();
+
+    let ticks: Vec<TickId> = items.tick_ids().collect();
+    assert_eq!(
+        ticks,
+        vec![TickId::new(10), TickId::new(11), TickId::new(12)]
+    );
+}
+
+#[test_log::test]
+fn shift_ticks_positive() {
+    let mut items = jump_and_moves();
+
+    items.shift_ticks(100).unwrap();
+
+    let ticks: Vec<u32> = items.tick_ids().map(|tick_id| tick_id.value()).collect();
+    assert_eq!(ticks, vec![110, 111, 112]);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(113));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(110));
+    items
+        .push(TickId::new(113), GameInput::Jumping(false))
+        .unwrap();
+}
+
+#[test_log::test]
+fn shift_ticks_negative() {
+    let mut items = jump_and_moves();
+
+    items.shift_ticks(-10).unwrap();
+
+    assert_eq!(items.front_tick_id(), Some(TickId::new(0)));
+    assert_eq!(items.back_tick_id(), Some(TickId::new(2)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(3));
+}
+
+#[test_log::test]
+fn shift_ticks_overflow_rejected() {
+    let mut items = jump_and_moves();
+    let before = items.to_vec_with_ticks();
+
+    assert!(matches!(
+        items.shift_ticks(-11),
+        Err(QueueError::TickOverflow { tick_id, delta: -11 }) if tick_id == TickId::new(10)
+    ));
+    assert!(matches!(
+        items.shift_ticks(i64::from(u32::MAX)),
+        Err(QueueError::TickOverflow { .. })
+    ));
+    assert_eq!(items.to_vec_with_ticks(), before);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn tick_layout_matches_pushes() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items.tick_layout(),
+        vec![
+            (0, TickId::new(10)),
+            (1, TickId::new(11)),
+            (2, TickId::new(12))
+        ]
+    );
+    assert!(Queue::<GameInput>::default().tick_layout().is_empty());
+}
+
+#[test_log::test]
+fn clear_if_broken_healthy_queue() {
+    let mut items = jump_and_moves();
+
+    assert!(!items.clear_if_broken(TickId::new(50)));
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn clear_if_broken_corrupted_queue() {
+    let mut items = gapped_queue();
+
+    assert!(items.clear_if_broken(TickId::new(50)));
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(50));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(50));
+}
+
+#[test_log::test]
+fn clear_if_broken_keeps_skipped_ticks() {
+    let mut items = Queue::new(TickId::new(0));
+    items.push(TickId::new(0), "Move 1").unwrap();
+    items.push_skipping(TickId::new(5), "Move 2").unwrap();
+
+    assert!(items.verify().is_err());
+    assert!(!items.clear_if_broken(TickId::new(50)));
+    assert_eq!(items.len(), 2);
+    assert_eq!(items.back_tick_id(), Some(TickId::new(5)));
+}
+
+#[test_log::test]
+fn iter_from_back_distance() {
+    let items = jump_and_moves();
+
+    let distances: Vec<(usize, TickId)> = items
+        .iter_from_back_distance()
+        .map(|(distance, info)| (distance, info.tick_id))
+        .collect();
+
+    assert_eq!(
+        distances,
+        vec![
+            (2, TickId::new(10)),
+            (1, TickId::new(11)),
+            (0, TickId::new(12))
+        ]
+    );
+}
+
+#[test_log::test]
+fn push_returns_written_tick() {
+    let mut items = Queue::new(TickId::new(23));
+
+    assert_eq!(
+        items
+            .push(TickId::new(23), GameInput::Jumping(true))
+            .unwrap(),
+        TickId::new(23)
+    );
+    assert_eq!(
+        items
+            .push(TickId::new(24), GameInput::Jumping(false))
+            .unwrap(),
+        items.back_tick_id().unwrap()
+    );
+}
+
+#[test_log::test]
+fn push_future_tick_is_gap() {
+    let mut items = Queue::new(TickId::new(23));
+
+    assert!(matches!(
+        items.push(TickId::new(25), GameInput::Jumping(true)),
+        Err(QueueError::GapDetected { expected, encountered })
+            if expected == TickId::new(23) && encountered == TickId::new(25)
+    ));
+}
+
+#[test_log::test]
+fn first_missing_up_to_covered() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.first_missing_up_to(TickId::new(12)), None);
+    assert_eq!(items.first_missing_up_to(TickId::new(5)), None);
+}
+
+#[test_log::test]
+fn first_missing_up_to_short() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items.first_missing_up_to(TickId::new(20)),
+        Some(TickId::new(13))
+    );
+}
+
+#[test_log::test]
+fn first_missing_up_to_empty_queue() {
+    let mut items = jump_and_moves();
+    let _ = items.take(3);
+
+    assert_eq!(
+        items.first_missing_up_to(TickId::new(20)),
+        Some(TickId::new(13))
+    );
+    assert_eq!(
+        Queue::<GameInput>::new(TickId::new(4)).first_missing_up_to(TickId::new(4)),
+        Some(TickId::new(4))
+    );
+}
+
+#[test_log::test]
+fn rev_iter_newest_first() {
+    let items = jump_and_moves();
+
+    let ticks: Vec<TickId> = items.rev_iter().map(|info| info.tick_id).collect();
+    assert_eq!(ticks.first(), items.back_tick_id().as_ref());
+    assert_eq!(ticks.last(), items.front_tick_id().as_ref());
+    assert_eq!(
+        ticks,
+        vec![TickId::new(12), TickId::new(11), TickId::new(10)]
+    );
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn on_discard_counts_discarded_items() {
+    let discarded = Arc::new(AtomicUsize::new(0));
+    let mut items = Queue::new(TickId::new(0));
+    for tick in 0..10 {
+        items.push(TickId::new(tick), tick).unwrap();
+    }
+    let counter = discarded.clone();
+    items.set_on_discard(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    items.discard_up_to(TickId::new(2));
+    assert_eq!(discarded.load(Ordering::SeqCst), 2);
+
+    items.discard_count(3);
+    assert_eq!(discarded.load(Ordering::SeqCst), 5);
+
+    items.cap_to_newest(2);
+    assert_eq!(discarded.load(Ordering::SeqCst), 8);
+
+    let _ = items.pop();
+    let _ = items.take(1);
+    assert_eq!(discarded.load(Ordering::SeqCst), 8);
+    assert!(items.is_empty());
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn on_discard_receives_items_in_order() {
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut items = jump_and_moves();
+    let sink = received.clone();
+    items.set_on_discard(move |info| sink.lock().unwrap().push(info.tick_id));
+
+    items.discard_count(2);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![TickId::new(10), TickId::new(11)]
+    );
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn on_discard_receives_replaced_items() {
+    let discarded = Arc::new(AtomicUsize::new(0));
+    let mut items = jump_and_moves();
+    let counter = discarded.clone();
+    items.set_on_discard(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    items.clear(TickId::new(0));
+    assert_eq!(discarded.load(Ordering::SeqCst), 3);
+
+    items.reset_with(TickId::new(0), [GameInput::Jumping(true)]);
+    items
+        .set_contents(vec![ItemInfo {
+            tick_id: TickId::new(5),
+            item: GameInput::Jumping(false),
+        }])
+        .unwrap();
+    assert_eq!(discarded.load(Ordering::SeqCst), 4);
+
+    let snapshot = items.snapshot();
+    items.restore(snapshot);
+    assert_eq!(discarded.load(Ordering::SeqCst), 5);
+
+    let target = Queue::new(TickId::new(6));
+    let diff = target.diff(&items);
+    items.apply_diff(&diff);
+    assert!(items.is_empty());
+    assert_eq!(discarded.load(Ordering::SeqCst), 6);
+    assert_eq!(items.metrics().discarded, 6);
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn on_discard_receives_truncated_items() {
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut items = jump_and_moves();
+    let sink = received.clone();
+    items.set_on_discard(move |info| sink.lock().unwrap().push(info.tick_id));
+
+    assert_eq!(items.truncate_to_len(1), 2);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![TickId::new(11), TickId::new(12)]
+    );
+    assert_eq!(items.metrics().discarded, 2);
+}
+
+#[test_log::test]
+fn next_pop_tick_id_tracks_front() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(10)));
+    let popped = items.pop().unwrap();
+    assert_eq!(popped.tick_id, TickId::new(10));
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(11)));
+    let _ = items.take(2);
+    assert_eq!(items.next_pop_tick_id(), None);
+}
+
+#[test_log::test]
+fn discard_up_to_counting_below_front() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.discard_up_to_counting(TickId::new(3)), 0);
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn discard_up_to_counting_some() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.discard_up_to_counting(TickId::new(12)), 2);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+}
+
+#[test_log::test]
+fn discard_up_to_counting_all() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.discard_up_to_counting(TickId::new(100)), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn default_step_queue_unchanged() {
+    let items: Queue<GameInput> = jump_and_moves();
+
+    assert_eq!(items.tick_step(), 1);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+    assert_eq!(items.index_of_tick_id(TickId::new(11)), Some(1));
+}
+
+#[test_log::test]
+fn split_off_front_zero() {
+    let mut items = jump_and_moves();
+
+    let front = items.split_off_front(0);
+    assert!(front.is_empty());
+    assert_eq!(front.expected_write_tick_id(), TickId::new(10));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn split_off_front_partial() {
+    let mut items = jump_and_moves();
+
+    let mut front = items.split_off_front(2);
+    assert_eq!(front.front_tick_id(), Some(TickId::new(10)));
+    assert_eq!(front.expected_write_tick_id(), TickId::new(12));
+    assert_eq!(
+        front.to_vec(),
+        vec![GameInput::MoveHorizontal(1), GameInput::Jumping(true)]
+    );
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+
+    front
+        .push(TickId::new(12), GameInput::Jumping(false))
+        .expect("split queue continues after its last item");
+}
+
+#[test_log::test]
+fn split_off_front_more_than_len() {
+    let mut items = jump_and_moves();
+
+    let front = items.split_off_front(10);
+    assert_eq!(front.len(), 3);
+    assert_eq!(front.expected_write_tick_id(), TickId::new(13));
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn iter_index_stays_exhausted() {
+    let items = jump_and_moves();
+
+    let mut iter = items.iter_index(2);
+    assert_eq!(iter.next().unwrap().tick_id, TickId::new(12));
+    for _ in 0..3 {
+        assert!(iter.next().is_none());
+    }
+}
+
+#[test_log::test]
+fn set_contents_valid() {
+    let mut items = jump_and_moves();
+
+    items
+        .set_contents(vec![
+            ItemInfo {
+                tick_id: TickId::new(40),
+                item: GameInput::Jumping(false),
+            },
+            ItemInfo {
+                tick_id: TickId::new(41),
+                item: GameInput::MoveHorizontal(7),
+            },
+        ])
+        .expect("contiguous contents");
+    assert_eq!(items.front_tick_id(), Some(TickId::new(40)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(42));
+    assert_eq!(items.len(), 2);
+}
+
+#[test_log::test]
+fn set_contents_gap_keeps_original() {
+    let mut items = jump_and_moves();
+
+    let result = items.set_contents(vec![
+        ItemInfo {
+            tick_id: TickId::new(40),
+            item: GameInput::Jumping(false),
+        },
+        ItemInfo {
+            tick_id: TickId::new(42),
+            item: GameInput::MoveHorizontal(7),
+        },
+    ]);
+    assert!(matches!(
+        result,
+        Err(QueueError::BrokenSequence {
+            at_index: 1,
+            expected: TickId(41),
+            encountered: TickId(42),
+        })
+    ));
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn set_contents_overflow_keeps_original() {
+    let mut items = jump_and_moves();
+
+    let result = items.set_contents(vec![
+        ItemInfo {
+            tick_id: TickId::new(TICK_ID_MAX),
+            item: GameInput::Jumping(false),
+        },
+        ItemInfo {
+            tick_id: TickId::new(TICK_ID_MAX),
+            item: GameInput::MoveHorizontal(7),
+        },
+    ]);
+    assert!(matches!(
+        result,
+        Err(QueueError::TickOverflow { tick_id, delta: 1 }) if tick_id == TickId::new(TICK_ID_MAX)
+    ));
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn set_contents_empty() {
+    let mut items = jump_and_moves();
+
+    items.set_contents(Vec::new()).unwrap();
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(13));
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn oldest_age_from_arrival_instants() {
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let mut items = Queue::new(TickId::new(10));
+    assert_eq!(items.oldest_age(start), None);
+
+    items
+        .push_at(TickId::new(10), GameInput::Jumping(true), start)
+        .unwrap();
+    items
+        .push_at(
+            TickId::new(11),
+            GameInput::MoveHorizontal(1),
+            start + Duration::from_millis(16),
+        )
+        .unwrap();
+    items
+        .push(TickId::new(12), GameInput::MoveHorizontal(2))
+        .unwrap();
+
+    let now = start + Duration::from_millis(50);
+    assert_eq!(items.oldest_age(now), Some(Duration::from_millis(50)));
+    assert_eq!(
+        items.arrival_instant(TickId::new(11)),
+        Some(start + Duration::from_millis(16))
+    );
+    assert_eq!(items.arrival_instant(TickId::new(12)), None);
+
+    let _ = items.pop();
+    assert_eq!(items.oldest_age(now), Some(Duration::from_millis(34)));
+    let _ = items.pop();
+    assert_eq!(items.oldest_age(now), None);
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn arrival_instants_follow_moved_items() {
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let mut items = Queue::new(TickId::new(0));
+    items.push_at(TickId::new(0), "a", start).unwrap();
+    items.push(TickId::new(1), "b").unwrap();
+
+    let _ = items.pop();
+    assert_eq!(items.requeue_front(), Some(TickId::new(2)));
+    assert_eq!(items.arrival_instant(TickId::new(2)), None);
+
+    let mut single = Queue::new(TickId::new(0));
+    single.push_at(TickId::new(0), "a", start).unwrap();
+    assert_eq!(single.requeue_front(), Some(TickId::new(1)));
+    assert_eq!(single.arrival_instant(TickId::new(1)), Some(start));
+
+    let _ = single.extract_if(|_| false);
+    assert_eq!(single.arrival_instant(TickId::new(1)), Some(start));
+}
+
+#[test_log::test]
+fn seek_read_to_backward_replays() {
+    let mut items = jump_and_moves();
+
+    items.seek_read_to(TickId::new(12)).unwrap();
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.len(), 3);
+
+    items.seek_read_to(TickId::new(11)).unwrap();
+    assert_eq!(
+        items.peek_at_read_offset(0).unwrap().item,
+        GameInput::Jumping(true)
+    );
+    let popped = items.pop().unwrap();
+    assert_eq!(popped.tick_id, TickId::new(11));
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(12)));
+    assert_eq!(items.front_tick_id(), Some(TickId::new(10)));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn seek_read_to_just_past_back() {
+    let mut items = jump_and_moves();
+
+    items.seek_read_to(TickId::new(13)).unwrap();
+    assert_eq!(items.next_pop_tick_id(), None);
+    assert!(items.pop().is_none());
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn seek_read_to_then_pop_keeps_skipped_items() {
+    let mut items = Queue::new(TickId::new(0));
+    for value in 0..3 {
+        items.push(TickId::new(value), value).unwrap();
+    }
+    items.seek_read_to(TickId::new(1)).unwrap();
+
+    assert!(!items.is_empty());
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.first(), Some(&0));
+    assert_eq!(items.front_tick_id(), Some(TickId::new(0)));
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(1)));
+    assert_eq!(items.peek_at_read_offset(1).map(|info| info.item), Some(2));
+
+    assert_eq!(items.pop().map(|info| info.item), Some(1));
+    assert_eq!(items.take(5), Some((TickId::new(2), vec![2])));
+    assert!(items.pop().is_none());
+    assert_eq!(items.to_vec(), vec![0, 1, 2]);
+    assert_eq!(items.metrics().discarded, 0);
+
+    items.seek_read_to(TickId::new(3)).unwrap();
+    assert!(items.pop().is_none());
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.first(), Some(&0));
+}
+
+#[test_log::test]
+fn seek_read_to_outside_range() {
+    let mut items = jump_and_moves();
+
+    assert!(matches!(
+        items.seek_read_to(TickId::new(9)),
+        Err(QueueError::TickNotPresent { tick_id: TickId(9) })
+    ));
+    assert!(matches!(
+        items.seek_read_to(TickId::new(14)),
+        Err(QueueError::TickNotPresent { .. })
+    ));
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(10)));
+}
+
+fn seeked_to_two() -> Queue<u32> {
+    let mut items = Queue::new(TickId::new(0));
+    for value in 0..5 {
+        items.push(TickId::new(value), value).unwrap();
+    }
+    items.seek_read_to(TickId::new(2)).unwrap();
+    items
+}
+
+#[test_log::test]
+fn seek_read_to_then_pop_expected() {
+    let mut items = seeked_to_two();
+
+    assert!(matches!(
+        items.pop_expected(TickId::new(0)),
+        Err(QueueError::UnexpectedReadTick {
+            expected: TickId(0),
+            encountered: TickId(2),
+        })
+    ));
+    assert_eq!(items.len(), 5);
+
+    let popped = items.pop_expected(TickId::new(2)).unwrap();
+    assert_eq!(popped.tick_id, TickId::new(2));
+}
+
+#[test_log::test]
+fn seek_read_to_then_take_keeping_minimum() {
+    let mut items = seeked_to_two();
+
+    let (first_tick_id, taken) = items.take_keeping_minimum(10, 2).unwrap();
+    assert_eq!(first_tick_id, TickId::new(2));
+    assert_eq!(taken, vec![2]);
+    assert_eq!(items.len(), 5);
+    assert!(items.take_keeping_minimum(10, 2).is_none());
+}
+
+#[test_log::test]
+fn seek_read_to_then_is_ready_for() {
+    let items = seeked_to_two();
+
+    assert!(!items.is_ready_for(TickId::new(0)));
+    assert!(items.is_ready_for(TickId::new(2)));
+}
+
+#[test_log::test]
+fn to_btree_map_keyed_by_tick() {
+    let items = jump_and_moves();
+
+    let map = items.to_btree_map();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&TickId::new(11)], GameInput::Jumping(true));
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        vec![TickId::new(10), TickId::new(11), TickId::new(12)]
+    );
+    assert_eq!(map.into_values().collect::<Vec<_>>(), items.to_vec());
+}
+
+#[test_log::test]
+fn metrics_count_operations() {
+    let mut items = jump_and_moves();
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+
+    let _ = items.pop();
+    let _ = items.take(1);
+    items.discard_up_to(TickId::new(13));
+    items.clear(TickId::new(20));
+    items
+        .push(TickId::new(20), GameInput::MoveHorizontal(3))
+        .unwrap();
+
+    assert_eq!(
+        *items.metrics(),
+        QueueMetrics {
+            pushed: 5,
+            popped: 2,
+            discarded: 2,
+            peak_len: 4,
+        }
+    );
+
+    items.reset_metrics();
+    assert_eq!(
+        *items.metrics(),
+        QueueMetrics {
+            peak_len: 1,
+            ..QueueMetrics::default()
+        }
+    );
+}
+
+#[test_log::test]
+fn missing_ticks_since_before_front() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items
+            .missing_ticks_since(TickId::new(7))
+            .collect::<Vec<_>>(),
+        vec![TickId::new(7), TickId::new(8), TickId::new(9)]
+    );
+}
+
+#[test_log::test]
+fn missing_ticks_since_at_or_after_front() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.missing_ticks_since(TickId::new(10)).count(), 0);
+    assert_eq!(items.missing_ticks_since(TickId::new(12)).count(), 0);
+}
+
+#[test_log::test]
+fn missing_ticks_since_on_empty_queue() {
+    let items = Queue::<GameInput>::new(TickId::new(5));
+
+    assert_eq!(
+        items
+            .missing_ticks_since(TickId::new(3))
+            .collect::<Vec<_>>(),
+        vec![TickId::new(3), TickId::new(4)]
+    );
+}
+
+#[test_log::test]
+fn last_pushed_is_back_item() {
+    let mut items = Queue::new(TickId::new(10));
+    assert!(items.last_pushed().is_none());
+
+    items
+        .push(TickId::new(10), GameInput::Jumping(true))
+        .unwrap();
+    items
+        .push(TickId::new(11), GameInput::MoveHorizontal(4))
+        .unwrap();
+
+    let last = items.last_pushed().unwrap();
+    assert_eq!(last.tick_id, TickId::new(11));
+    assert_eq!(last.item, GameInput::MoveHorizontal(4));
+}
+
+#[test_log::test]
+fn downsample_keeps_every_second() {
+    let mut items = Queue::from_items(TickId::new(20), vec!["a", "b", "c", "d", "e"]);
+
+    let removed = items.downsample(2);
+    assert_eq!(
+        removed,
+        vec![
+            ItemInfo::new(TickId::new(21), "b"),
+            ItemInfo::new(TickId::new(23), "d"),
+        ]
+    );
+    assert_eq!(items.to_vec(), vec!["a", "c", "e"]);
+    assert_eq!(
+        items.tick_ids().collect::<Vec<_>>(),
+        vec![TickId::new(20), TickId::new(21), TickId::new(22)]
+    );
+    assert_eq!(items.expected_write_tick_id(), TickId::new(23));
+    assert!(items.verify().is_ok());
+}
+
+#[test_log::test]
+fn downsample_keep_every_one_is_noop() {
+    let mut items = jump_and_moves();
+
+    assert!(items.downsample(1).is_empty());
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn matches_expected_sequence() {
+    let items = jump_and_moves();
+
+    assert!(items.matches(&[
+        (TickId::new(10), GameInput::MoveHorizontal(1)),
+        (TickId::new(11), GameInput::Jumping(true)),
+        (TickId::new(12), GameInput::MoveHorizontal(-1)),
+    ]));
+}
+
+#[test_log::test]
+fn matches_rejects_payload_mismatch() {
+    let items = jump_and_moves();
+
+    assert!(!items.matches(&[
+        (TickId::new(10), GameInput::MoveHorizontal(1)),
+        (TickId::new(11), GameInput::Jumping(false)),
+        (TickId::new(12), GameInput::MoveHorizontal(-1)),
+    ]));
+}
+
+#[test_log::test]
+fn matches_rejects_length_mismatch() {
+    let items = jump_and_moves();
+
+    assert!(!items.matches(&[
+        (TickId::new(10), GameInput::MoveHorizontal(1)),
+        (TickId::new(11), GameInput::Jumping(true)),
+    ]));
+}
+
+#[test_log::test]
+fn remaining_capacity_unbounded() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.remaining_capacity(), None);
+}
+
+#[test_log::test]
+fn remaining_capacity_partially_full() {
+    let mut items = jump_and_moves();
+    items.set_max_len(Some(5));
+
+    assert_eq!(items.remaining_capacity(), Some(2));
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    assert_eq!(items.remaining_capacity(), Some(1));
+}
+
+#[test_log::test]
+fn remaining_capacity_full_rejects_push() {
+    let mut items = jump_and_moves();
+    items.set_max_len(Some(3));
+
+    assert_eq!(items.remaining_capacity(), Some(0));
+    assert!(matches!(
+        items.push(TickId::new(13), GameInput::Jumping(false)),
+        Err(QueueError::Full { max_len: 3 })
+    ));
+    assert_eq!(items.len(), 3);
+
+    let _ = items.pop();
+    assert_eq!(items.remaining_capacity(), Some(1));
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+}
+
+#[test_log::test]
+fn pop_exact_with_fewer_items() {
+    let mut items = jump_and_moves();
+
+    assert!(items.pop_exact(4).is_none());
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn pop_exact_with_exact_items() {
+    let mut items = jump_and_moves();
+
+    let (tick_id, popped) = items.pop_exact(3).unwrap();
+    assert_eq!(tick_id, TickId::new(10));
+    assert_eq!(popped.len(), 3);
+    assert!(items.is_empty());
+}
+
+#[test_log::test]
+fn pop_exact_with_more_items() {
+    let mut items = jump_and_moves();
+
+    let (tick_id, popped) = items.pop_exact(2).unwrap();
+    assert_eq!(tick_id, TickId::new(10));
+    assert_eq!(
+        popped,
+        vec![GameInput::MoveHorizontal(1), GameInput::Jumping(true)]
+    );
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+}
+
+#[test_log::test]
+fn run_length_encode_single_run() {
+    let items = Queue::from_items(TickId::new(4), vec!["hold"; 5]);
+
+    assert_eq!(items.run_length_encode(), vec![(TickId::new(4), 5, "hold")]);
+}
+
+#[test_log::test]
+fn run_length_encode_alternating() {
+    let items = Queue::from_items(TickId::new(0), vec!["a", "b", "a"]);
+
+    assert_eq!(
+        items.run_length_encode(),
+        vec![
+            (TickId::new(0), 1, "a"),
+            (TickId::new(1), 1, "b"),
+            (TickId::new(2), 1, "a"),
+        ]
+    );
+}
+
+#[test_log::test]
+fn run_length_encode_mixed() {
+    let items = Queue::from_items(TickId::new(10), vec!["a", "a", "b", "c", "c", "c"]);
+
+    assert_eq!(
+        items.run_length_encode(),
+        vec![
+            (TickId::new(10), 2, "a"),
+            (TickId::new(12), 1, "b"),
+            (TickId::new(13), 3, "c"),
+        ]
+    );
+    assert!(Queue::<&str>::new(TickId::new(0))
+        .run_length_encode()
+        .is_empty());
+}
+
+#[test_log::test]
+fn from_run_length_round_trip() {
+    let original = Queue::from_items(TickId::new(30), vec!["a", "a", "b", "c", "c", "c"]);
+
+    let encoded = original.run_length_encode();
+    let start_tick_id = encoded[0].0;
+    let runs: Vec<(usize, &str)> = encoded
+        .into_iter()
+        .map(|(_, length, item)| (length, item))
+        .collect();
+    let decoded = Queue::from_run_length(start_tick_id, &runs);
+
+    assert_eq!(decoded, original);
+    assert_eq!(decoded.expected_write_tick_id(), TickId::new(36));
+}
+
+#[test_log::test]
+fn from_run_length_skips_empty_runs() {
+    let decoded = Queue::from_run_length(TickId::new(0), &[(2, "a"), (0, "b"), (1, "c")]);
+
+    assert_eq!(decoded.to_vec(), vec!["a", "a", "c"]);
+}
+
+#[test_log::test]
+fn pop_at_least_skips_stale_items() {
+    let mut items = jump_and_moves();
+
+    let popped = items.pop_at_least(TickId::new(11)).unwrap();
+    assert_eq!(popped.tick_id, TickId::new(11));
+    assert_eq!(popped.item, GameInput::Jumping(true));
+    assert_eq!(items.front_tick_id(), Some(TickId::new(12)));
+}
+
+#[test_log::test]
+fn pop_at_least_target_not_buffered() {
+    let mut items = jump_and_moves();
+
+    assert!(items.pop_at_least(TickId::new(20)).is_none());
+    assert!(items.is_empty());
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn pop_at_least_on_empty_queue() {
+    let mut items = Queue::<GameInput>::new(TickId::new(3));
+
+    assert!(items.pop_at_least(TickId::new(3)).is_none());
+}
+
+#[test_log::test]
+fn builder_with_several_options() {
+    let mut items = QueueBuilder::new()
+        .initial_tick(TickId::new(8))
+        .capacity(16)
+        .max_len(2)
+        .step(4)
+        .build();
+
+    assert!(items.capacity() >= 16);
+    assert_eq!(items.tick_step(), 4);
+    items
+        .push(TickId::new(8), GameInput::Jumping(true))
+        .unwrap();
+    items
+        .push(TickId::new(12), GameInput::MoveHorizontal(1))
+        .unwrap();
+    assert!(matches!(
+        items.push(TickId::new(16), GameInput::MoveHorizontal(2)),
+        Err(QueueError::Full { max_len: 2 })
+    ));
+}
+
+#[test_log::test]
+fn builder_defaults_match_new() {
+    let items: Queue<GameInput> = QueueBuilder::default().build();
+
+    assert_eq!(items, Queue::new(TickId::new(0)));
+    assert_eq!(items.remaining_capacity(), None);
+}
+
+#[test_log::test]
+fn builder_is_copy_and_debug_for_any_payload() {
+    struct Opaque;
+
+    let builder = QueueBuilder::<Opaque>::new().step(2);
+    let copy = builder;
+
+    assert_eq!(format!("{copy:?}"), format!("{builder:?}"));
+    assert!(format!("{builder:?}").contains("step: 2"));
+}
+
+#[test_log::test]
+fn snapshot_and_restore() {
+    let mut items = jump_and_moves();
+    let snapshot = items.snapshot();
+
+    let _ = items.pop();
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    assert_ne!(items.snapshot(), snapshot);
+
+    items.restore(snapshot.clone());
+    assert_eq!(items.snapshot(), snapshot);
+    assert_eq!(items, jump_and_moves());
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(10)));
+}
+
+#[test_log::test]
+fn iter_after_middle_tick() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items
+            .iter_after(TickId::new(10))
+            .map(|item_info| item_info.tick_id)
+            .collect::<Vec<_>>(),
+        vec![TickId::new(11), TickId::new(12)]
+    );
+}
+
+#[test_log::test]
+fn iter_after_below_front() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.iter_after(TickId::new(2)).count(), 3);
+}
+
+#[test_log::test]
+fn iter_after_back() {
+    let items = jump_and_moves();
+
+    assert_eq!(items.iter_after(TickId::new(12)).count(), 0);
+    assert_eq!(items.iter_after(TickId::new(40)).count(), 0);
+}
+
+#[test_log::test]
+fn encode_into_visits_front_to_back() {
+    let items = jump_and_moves();
+
+    let mut buffer = Vec::new();
+    items.encode_into(|item_info| buffer.push(item_info.tick_id.value() as u8));
+    assert_eq!(buffer, vec![10, 11, 12]);
+}
+
+#[test_log::test]
+fn rotate_read_cursor_wraps() {
+    let mut items = jump_and_moves();
+
+    items.rotate_read_cursor(2);
+    assert_eq!(
+        items.peek_at_read_offset(0).unwrap().tick_id,
+        TickId::new(12)
+    );
+    items.rotate_read_cursor(2);
+    assert_eq!(
+        items.peek_at_read_offset(0).unwrap().tick_id,
+        TickId::new(11)
+    );
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(10)));
+}
+
+#[test_log::test]
+fn rotate_read_cursor_by_len_is_noop() {
+    let mut items = jump_and_moves();
+    items.rotate_read_cursor(1);
+
+    items.rotate_read_cursor(items.len());
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(11)));
+}
+
+#[test_log::test]
+fn rotate_read_cursor_from_write_tick() {
+    let mut items = jump_and_moves();
+    items.seek_read_to(TickId::new(13)).unwrap();
+
+    items.rotate_read_cursor(items.len());
+    assert_eq!(items.next_pop_tick_id(), None);
+    assert_eq!(items.expected_read_tick_id(), TickId::new(13));
+
+    items.rotate_read_cursor(1);
+    assert_eq!(items.next_pop_tick_id(), Some(TickId::new(10)));
+}
+
+#[test_log::test]
+fn rotate_read_cursor_then_pop_keeps_skipped_items() {
+    let mut items = Queue::new(TickId::new(0));
+    for value in 0..4 {
+        items.push(TickId::new(value), value).unwrap();
+    }
+
+    items.rotate_read_cursor(3);
+    let popped = items.pop().unwrap();
+    assert_eq!(popped.tick_id, TickId::new(3));
+    assert_eq!(items.len(), 4);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(0)));
+    assert_eq!(items.metrics().discarded, 0);
+
+    items.rotate_read_cursor(1);
+    assert_eq!(items.pop().map(|info| info.item), Some(0));
+    assert_eq!(items.to_vec(), vec![1, 2, 3]);
+}
+
+#[test_log::test]
+fn empty_at_rejects_push_at_zero() {
+    let mut items = Queue::empty_at(TickId::new(100));
+
+    assert!(matches!(
+        items.push(TickId::new(0), GameInput::Jumping(true)),
+        Err(QueueError::DuplicateTickId { tick_id: TickId(0) })
+    ));
+    assert!(items.is_empty());
+}
+
+#[test_log::test]
+fn average_payload_of_latencies() {
+    let items = Queue::from_items(TickId::new(0), vec![10_u32, 20, 40, 50]);
+
+    assert_eq!(items.average_payload(), Some(30.0));
+    assert_eq!(Queue::<u8>::new(TickId::new(0)).average_payload(), None);
+}
+
+#[test_log::test]
+fn push_resent_tick_is_duplicate() {
+    let mut items = jump_and_moves();
+
+    assert!(matches!(
+        items.push(TickId::new(11), GameInput::Jumping(true)),
+        Err(QueueError::DuplicateTickId {
+            tick_id: TickId(11)
+        })
+    ));
+    assert_eq!(items.len(), 3);
+}
+
+#[test_log::test]
+fn push_skipping_misaligned_is_gap() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+
+    assert!(matches!(
+        items.push_skipping(TickId::new(10), GameInput::Jumping(true)),
+        Err(QueueError::GapDetected { .. })
+    ));
+}
+
+#[test_log::test]
+fn write_debug_lines() {
+    let items = Queue::from_items(TickId::new(10), vec!["jump", "move"]);
+
+    let mut output = Vec::new();
+    items.write_debug(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Queue(expected_write=tick:0000000C)\ntick:0000000A: jump\ntick:0000000B: move\n"
+    );
+}
+
+#[test_log::test]
+fn longest_run_single() {
+    let items = Queue::from_items(TickId::new(5), vec![false, true, true, true, false]);
+
+    assert_eq!(items.longest_run(|held| *held), Some((TickId::new(6), 3)));
+}
+
+#[test_log::test]
+fn longest_run_picks_longest_then_first() {
+    let items = Queue::from_items(
+        TickId::new(0),
+        vec![true, false, true, true, false, true, true, false, true],
+    );
+
+    assert_eq!(items.longest_run(|held| *held), Some((TickId::new(2), 2)));
+    assert_eq!(items.longest_run(|held| !*held), Some((TickId::new(1), 1)));
+}
+
+#[test_log::test]
+fn longest_run_no_matches() {
+    let items = jump_and_moves();
+
+    assert_eq!(
+        items.longest_run(|input| *input == GameInput::Jumping(false)),
+        None
+    );
+}
+
+#[test_log::test]
+fn write_read_gap_follows_push_and_pop() {
+    let mut items = Queue::new(TickId::new(10));
+    assert_eq!(items.write_read_gap(), 0);
+
+    items
+        .push(TickId::new(10), GameInput::Jumping(true))
+        .unwrap();
+    items
+        .push(TickId::new(11), GameInput::MoveHorizontal(1))
+        .unwrap();
+    assert_eq!(items.write_read_gap(), 2);
+
+    let _ = items.pop();
+    assert_eq!(items.write_read_gap(), 1);
+    assert_eq!(items.write_read_gap(), items.len() as u32);
+}
+
+#[test_log::test]
+fn write_read_gap_after_discard() {
+    let mut items = jump_and_moves();
+
+    items.discard_up_to(TickId::new(12));
+    assert_eq!(items.write_read_gap(), 1);
+    items.discard_up_to(TickId::new(13));
+    assert_eq!(items.write_read_gap(), 0);
+
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    assert_eq!(items.write_read_gap(), 1);
+}
+
+#[test_log::test]
+fn write_read_gap_over_skipped_ticks() {
+    let mut items = skipped_to_five();
+
+    assert_eq!(items.write_read_gap(), 3);
+    let _ = items.pop();
+    assert_eq!(items.write_read_gap(), 2);
+}
+
+#[test_log::test]
+fn pairs_round_trip() {
+    let items = jump_and_moves();
+
+    let pairs = items.to_pairs();
+    assert_eq!(pairs[0], (TickId::new(10), GameInput::MoveHorizontal(1)));
+    let rebuilt = Queue::from_pairs(TickId::new(10), 1, pairs).unwrap();
+    assert_eq!(rebuilt, items);
+}
+
+#[test_log::test]
+fn pairs_round_trip_empty() {
+    let items = Queue::<GameInput>::new(TickId::new(42));
+
+    let rebuilt = Queue::from_pairs(
+        items.expected_write_tick_id(),
+        items.tick_step(),
+        items.to_pairs(),
+    )
+    .unwrap();
+    assert_eq!(rebuilt, items);
+    assert_eq!(rebuilt.expected_write_tick_id(), TickId::new(42));
+}
+
+#[test_log::test]
+fn pairs_round_trip_with_step() {
+    let mut items = Queue::with_step(TickId::new(8), 4);
+    items.push(TickId::new(8), "Sample 1").unwrap();
+    items.push(TickId::new(12), "Sample 2").unwrap();
+
+    let rebuilt = Queue::from_pairs(
+        items.front_tick_id().unwrap(),
+        items.tick_step(),
+        items.to_pairs(),
+    )
+    .unwrap();
+    assert_eq!(rebuilt, items);
+    assert_eq!(rebuilt.tick_step(), 4);
+    assert_eq!(rebuilt.expected_write_tick_id(), TickId::new(16));
+}
+
+#[test_log::test]
+fn from_pairs_rejects_gap() {
+    let result = Queue::from_pairs(
+        TickId::new(4),
+        1,
+        vec![
+            (TickId::new(4), "a"),
+            (TickId::new(5), "b"),
+            (TickId::new(7), "c"),
+        ],
+    );
+
+    assert!(matches!(
+        result,
+        Err(QueueError::BrokenSequence {
+            at_index: 2,
+            expected: TickId(6),
+            encountered: TickId(7),
+        })
+    ));
+    assert!(matches!(
+        Queue::from_pairs(TickId::new(3), 1, vec![(TickId::new(4), "a")]),
+        Err(QueueError::BrokenSequence {
+            at_index: 0,
+            expected: TickId(3),
+            encountered: TickId(4),
+        })
+    ));
+}
+
+#[test_log::test]
+fn ends_of_empty_queue() {
+    let items = Queue::<GameInput>::new(TickId::new(0));
+
+    assert!(items.ends().is_none());
+}
+
+#[test_log::test]
+fn ends_of_single_item() {
+    let items = Queue::from_items(TickId::new(3), vec!["only"]);
+
+    let (front, back) = items.ends().unwrap();
+    assert!(std::ptr::eq(front, back));
+    assert_eq!(front.tick_id, TickId::new(3));
+}
+
+#[test_log::test]
+fn ends_of_several_items() {
+    let items = jump_and_moves();
+
+    let (front, back) = items.ends().unwrap();
+    assert_eq!(front.tick_id, TickId::new(10));
+    assert_eq!(back.tick_id, TickId::new(12));
+}
+
+#[test_log::test]
+fn requeue_front_moves_item_to_back() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.requeue_front(), Some(TickId::new(13)));
+    assert_eq!(items.len(), 3);
+    assert!(items.matches(&[
+        (TickId::new(11), GameInput::Jumping(true)),
+        (TickId::new(12), GameInput::MoveHorizontal(-1)),
+        (TickId::new(13), GameInput::MoveHorizontal(1)),
+    ]));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(14));
+    assert_eq!(
+        Queue::<GameInput>::new(TickId::new(0)).requeue_front(),
+        None
+    );
+}
+
+#[test_log::test]
+fn requeue_front_at_tick_space_end() {
+    let mut items = Queue::new(TickId::new(TICK_ID_MAX - 1));
+    items
+        .push(TickId::new(TICK_ID_MAX - 1), GameInput::Jumping(true))
+        .unwrap();
+
+    assert_eq!(items.requeue_front(), None);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(TICK_ID_MAX - 1)));
+    assert_eq!(items.expected_write_tick_id(), TickId::new(TICK_ID_MAX));
+    assert!(items.verify().is_ok());
+}
+
+#[test_log::test]
+fn requeue_front_leaves_metrics_balanced() {
+    let mut items = jump_and_moves();
+    let before = *items.metrics();
+
+    items.requeue_front().unwrap();
+
+    assert_eq!(*items.metrics(), before);
+    assert_eq!(
+        items.metrics().pushed - items.metrics().popped - items.metrics().discarded,
+        items.len() as u64
+    );
+}
+
+#[test_log::test]
+fn merge_iter_disjoint() {
+    let local = Queue::from_items(TickId::new(5), vec!["l5", "l6"]);
+    let remote = Queue::from_items(TickId::new(1), vec!["r1", "r2"]);
+
+    assert_eq!(
+        merge_iter(&local, &remote)
+            .map(|item_info| item_info.item)
+            .collect::<Vec<_>>(),
+        vec!["r1", "r2", "l5", "l6"]
+    );
+}
+
+#[test_log::test]
+fn merge_iter_interleaved() {
+    let mut local = Queue::with_step(TickId::new(0), 2);
+    let mut remote = Queue::with_step(TickId::new(1), 2);
+    for tick in 0..3 {
+        local.push(TickId::new(tick * 2), tick * 2).unwrap();
+        remote
+            .push(TickId::new(tick * 2 + 1), tick * 2 + 1)
+            .unwrap();
+    }
+
+    assert_eq!(
+        merge_iter(&local, &remote)
+            .map(|item_info| item_info.item)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4, 5]
+    );
+}
+
+#[test_log::test]
+fn merge_iter_ties_prefer_first() {
+    let local = Queue::from_items(TickId::new(3), vec!["local", "local"]);
+    let remote = Queue::from_items(TickId::new(4), vec!["remote"]);
+
+    assert_eq!(
+        merge_iter(&local, &remote)
+            .map(|item_info| (item_info.tick_id.value(), item_info.item))
+            .collect::<Vec<_>>(),
+        vec![(3, "local"), (4, "local"), (4, "remote")]
+    );
+}
+
+#[test_log::test]
+fn monotonic_check_allows_normal_consumption() {
+    let mut items = jump_and_moves();
+    items.enable_monotonic_check();
+
+    let _ = items.pop();
+    let _ = items.take(1);
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    let _ = items.pop();
+    let _ = items.pop();
+}
+
+#[cfg(debug_assertions)]
+#[test_log::test]
+#[should_panic(expected = "after already popping")]
+fn monotonic_check_catches_repeated_tick() {
+    let mut items = jump_and_moves();
+    items.enable_monotonic_check();
+    let checkpoint = items.snapshot();
+
+    let _ = items.pop();
+    items.restore(checkpoint);
+    let _ = items.pop();
+}
+
+#[test_log::test]
+fn trim_to_range_inside_data() {
+    let mut items = Queue::from_items(TickId::new(0), vec!["a", "b", "c", "d", "e"]);
+
+    assert_eq!(items.trim_to_range(TickId::new(1)..=TickId::new(3)), 2);
+    assert_eq!(items.to_vec(), vec!["b", "c", "d"]);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(4));
+}
+
+#[test_log::test]
+fn trim_to_range_covering_everything() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.trim_to_range(TickId::new(0)..=TickId::new(100)), 0);
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn trim_to_range_disjoint() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.trim_to_range(TickId::new(20)..=TickId::new(30)), 3);
+    assert!(items.is_empty());
+
+    let mut items = jump_and_moves();
+    assert_eq!(items.trim_to_range(TickId::new(0)..=TickId::new(5)), 3);
+    assert!(items.is_empty());
+    assert_eq!(items.expected_write_tick_id(), TickId::new(10));
+}
+
+#[cfg(feature = "runtime")]
+#[test_log::test]
+fn trim_to_range_reports_both_ends_to_on_discard() {
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut items = Queue::from_items(TickId::new(0), vec!["a", "b", "c", "d", "e"]);
+    let sink = received.clone();
+    items.set_on_discard(move |info| sink.lock().unwrap().push(info.tick_id));
+
+    assert_eq!(items.trim_to_range(TickId::new(1)..=TickId::new(2)), 3);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![TickId::new(0), TickId::new(3), TickId::new(4)]
+    );
+    assert_eq!(items.metrics().discarded, 3);
+}
+
+#[test_log::test]
+fn ticks_until_overflow_near_max() {
+    let mut items = Queue::new(TickId::new(TICK_ID_MAX - 2));
+
+    assert_eq!(items.ticks_until_overflow(), 2);
+    items
+        .push(TickId::new(TICK_ID_MAX - 2), GameInput::Jumping(true))
+        .unwrap();
+    assert_eq!(items.ticks_until_overflow(), 1);
+}
+
+#[test_log::test]
+fn ticks_until_overflow_mid_range() {
+    let items = Queue::<GameInput>::new(TickId::new(1 << 31));
+
+    assert_eq!(items.ticks_until_overflow(), (1 << 31) - 1);
+}
+
+#[test_log::test]
+fn ticks_until_overflow_at_max() {
+    let items = Queue::<GameInput>::new(TickId::new(TICK_ID_MAX));
+
+    assert_eq!(items.ticks_until_overflow(), 0);
+}
+
+#[test_log::test]
+fn push_at_max_is_tick_overflow() {
+    let mut items = Queue::new(TickId::new(TICK_ID_MAX - 1));
+    items
+        .push(TickId::new(TICK_ID_MAX - 1), GameInput::Jumping(true))
+        .unwrap();
+
+    assert!(matches!(
+        items.push(TickId::new(TICK_ID_MAX), GameInput::Jumping(false)),
+        Err(QueueError::TickOverflow { tick_id, delta: 1 }) if tick_id == TickId::new(TICK_ID_MAX)
+    ));
+    assert!(matches!(
+        items.push_slice(TickId::new(TICK_ID_MAX), &[GameInput::Jumping(false)]),
+        Err(QueueError::TickOverflow { .. })
+    ));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items.expected_write_tick_id(), TickId::new(TICK_ID_MAX));
+
+    let mut skipping = Queue::new(TickId::new(0));
+    assert!(matches!(
+        skipping.push_skipping(TickId::new(TICK_ID_MAX), GameInput::Jumping(false)),
+        Err(QueueError::TickOverflow { .. })
+    ));
+    assert!(skipping.is_empty());
+}
+
+#[test_log::test]
+fn len_prefixed_round_trip() {
+    use std::io::{Read, Write};
+
+    let items = Queue::from_items(TickId::new(0x0102_0304), vec![7_u16, 300, 65535]);
+
+    let mut encoded = Vec::new();
+    items
+        .encode_len_prefixed(&mut encoded, |item, w| w.write_all(&item.to_be_bytes()))
+        .unwrap();
+    assert_eq!(&encoded[..8], &[1, 2, 3, 4, 0, 0, 0, 3]);
+    assert_eq!(encoded.len(), 8 + 3 * 2);
+
+    let decoded = Queue::decode_len_prefixed(&mut encoded.as_slice(), |r| {
+        let mut bytes = [0; 2];
+        r.read_exact(&mut bytes)?;
+        Ok(u16::from_be_bytes(bytes))
+    })
+    .unwrap();
+    assert_eq!(decoded, items);
+}
+
+#[test_log::test]
+fn len_prefixed_rejects_header_past_tick_space() {
+    use std::io::Read;
+
+    let mut encoded = Vec::new();
+    encoded.extend_from_slice(&u32::MAX.to_be_bytes());
+    encoded.extend_from_slice(&2_u32.to_be_bytes());
+    encoded.extend_from_slice(&[7, 8]);
+
+    let err = Queue::decode_len_prefixed(&mut encoded.as_slice(), |r| {
+        let mut byte = [0; 1];
+        r.read_exact(&mut byte)?;
+        Ok(byte[0])
+    })
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test_log::test]
+fn len_prefixed_rejects_step() {
+    use std::io::Write;
+
+    let mut items = Queue::with_step(TickId::new(0), 4);
+    items.push(TickId::new(0), 7_u16).unwrap();
+    items.push(TickId::new(4), 8).unwrap();
+
+    let mut encoded = Vec::new();
+    let err = items
+        .encode_len_prefixed(&mut encoded, |item, w| w.write_all(&item.to_be_bytes()))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(encoded.is_empty());
+}
+
+#[test_log::test]
+fn drain_into_reuses_buffer() {
+    let mut items = Queue::from_items(TickId::new(0), vec!["a", "b", "c", "d", "e"]);
+    let mut out = Vec::with_capacity(8);
+
+    assert_eq!(items.drain_into(2, &mut out), 2);
+    assert_eq!(items.drain_into(5, &mut out), 3);
+    assert_eq!(items.drain_into(5, &mut out), 0);
+
+    assert!(items.is_empty());
+    assert_eq!(
+        out.iter()
+            .map(|item_info| item_info.item)
+            .collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d", "e"]
+    );
+    assert_eq!(out[4].tick_id, TickId::new(4));
+    assert_eq!(items.expected_read_tick_id(), TickId::new(5));
+}
+
+#[test_log::test]
+fn auto_trim_target_keeps_rolling_window() {
+    let mut items = jump_and_moves();
+    items.set_auto_trim_target(Some(3));
+
+    items
+        .push(TickId::new(13), GameInput::Jumping(false))
+        .unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items.front_tick_id(), Some(TickId::new(11)));
+    assert_eq!(items.back_tick_id(), Some(TickId::new(13)));
+    assert_eq!(items.metrics().discarded, 1);
+
+    items.set_auto_trim_target(None);
+    items
+        .push(TickId::new(14), GameInput::Jumping(true))
+        .unwrap();
+    assert_eq!(items.len(), 4);
+}
+
+#[test_log::test]
+fn has_consecutive_from_fully_present() {
+    let items = jump_and_moves();
+
+    assert!(items.has_consecutive_from(TickId::new(10), 3));
+    assert!(items.has_consecutive_from(TickId::new(11), 2));
+}
+
+#[test_log::test]
+fn has_consecutive_from_partially_present() {
+    let items = jump_and_moves();
+
+    assert!(!items.has_consecutive_from(TickId::new(11), 3));
+    assert!(!gapped_queue().has_consecutive_from(TickId::new(1), 3));
+}
+
+#[test_log::test]
+fn has_consecutive_from_before_front() {
+    let items = jump_and_moves();
+
+    assert!(!items.has_consecutive_from(TickId::new(9), 2));
+}
+
+#[test_log::test]
+fn take_if_front_matching() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(
+        items.take_if_front(TickId::new(10)),
+        Some(GameInput::MoveHorizontal(1))
+    );
+    assert_eq!(items.front_tick_id(), Some(TickId::new(11)));
+}
+
+#[test_log::test]
+fn take_if_front_not_front() {
+    let mut items = jump_and_moves();
+
+    assert_eq!(items.take_if_front(TickId::new(11)), None);
+    assert_eq!(items, jump_and_moves());
+}
+
+#[test_log::test]
+fn take_if_front_empty() {
+    let mut items = Queue::<GameInput>::new(TickId::new(10));
+
+    assert_eq!(items.take_if_front(TickId::new(10)), None);
+}
+
+#[test_log::test]
+fn clone_range_fully_contained() {
+    let items = Queue::from_items(TickId::new(0), vec!["a", "b", "c", "d", "e"]);
+
+    let window = items.clone_range(TickId::new(1)..=TickId::new(3));
+    assert!(window.matches(&[
+        (TickId::new(1), "b"),
+        (TickId::new(2), "c"),
+        (TickId::new(3), "d"),
+    ]));
+    assert_eq!(window.expected_write_tick_id(), TickId::new(4));
+    assert_eq!(items.len(), 5);
+}
+
+#[test_log::test]
+fn clone_range_partial_overlap() {
+    let items = jump_and_moves();
+
+    let window = items.clone_range(TickId::new(11)..=TickId::new(40));
+    assert_eq!(window.front_tick_id(), Some(TickId::new(11)));
+    assert_eq!(window.len(), 2);
+    assert_eq!(window.expected_write_tick_id(), TickId::new(13));
+}
+
+#[test_log::test]
+fn clone_range_disjoint() {
+    let items = jump_and_moves();
+
+    let window = items.clone_range(TickId::new(30)..=TickId::new(40));
+    assert!(window.is_empty());
+    assert_eq!(window.expected_write_tick_id(), TickId::new(30));
+}